@@ -0,0 +1,312 @@
+use std::fmt::{Debug, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use iced::futures::{future, SinkExt};
+use iced::{subscription, Subscription};
+use image::{imageops, ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::api;
+use crate::api::microsoft;
+use crate::api::microsoft::DeviceCode;
+use crate::launch::Session;
+use crate::{paths, Errors};
+
+const NONCE_LENGTH: usize = 12;
+
+const EXPIRY_MARGIN: u64 = 300;
+
+pub(crate) const HEAD_SIZE: u32 = 32;
+
+#[skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Account {
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    label: Option<String>,
+    skin: Option<String>,
+    #[serde(flatten)]
+    microsoft: Option<Tokens>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Tokens {
+    refresh_token: String,
+    access_token: String,
+    expires_at: u64,
+    xuid: String,
+}
+
+impl Debug for Account {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Account")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Account {
+    pub(crate) fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    pub(crate) fn is_microsoft(&self) -> bool {
+        self.microsoft.is_some()
+    }
+}
+
+pub(crate) async fn load() -> Result<Vec<Account>, Errors> {
+    let path = paths::ACCOUNTS.clone()?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read(path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    if data.len() < NONCE_LENGTH {
+        return Err(Errors::Encryption);
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+    let plaintext = cipher()
+        .await?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Errors::Encryption)?;
+    serde_json::from_slice(&plaintext).map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn stored() -> Result<Vec<Account>, Errors> {
+    match load().await {
+        Err(Errors::Encryption) => {
+            let path = paths::ACCOUNTS.clone()?;
+            fs::rename(&path, path.with_extension("dat.unreadable"))
+                .await
+                .map_err(|error| Errors::Io(error.kind()))?;
+            Ok(vec![])
+        }
+        result => result,
+    }
+}
+
+pub(crate) async fn save(accounts: &[Account]) -> Result<(), Errors> {
+    let plaintext =
+        serde_json::to_vec(accounts).map_err(|error| Errors::Json(error.to_string()))?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher()
+        .await?
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| Errors::Encryption)?;
+    let mut data = nonce.to_vec();
+    data.extend(ciphertext);
+    fs::write(paths::ACCOUNTS.clone()?, data)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+pub(crate) fn login(code: DeviceCode) -> Subscription<Result<Vec<Account>, Errors>> {
+    subscription::channel(code.user_code.clone(), 1, |mut output| async move {
+        let _ = output.send(sign_in(code).await).await;
+
+        future::pending().await
+    })
+}
+
+async fn sign_in(code: DeviceCode) -> Result<Vec<Account>, Errors> {
+    let token = microsoft::poll_token(&code).await?;
+    let mut account = authenticate(token.refresh_token, &token.access_token).await?;
+
+    let mut accounts = stored().await?;
+    match accounts.iter_mut().find(|other| other.id == account.id) {
+        Some(other) => {
+            account.label = other.label.take();
+            *other = account;
+        }
+        None => accounts.push(account),
+    }
+    save(&accounts).await?;
+    Ok(accounts)
+}
+
+pub(crate) async fn add_offline(name: String) -> Result<Vec<Account>, Errors> {
+    let mut accounts = stored().await?;
+    let id = Session::offline(&name).uuid();
+    if !accounts.iter().any(|account| account.id == id) {
+        accounts.push(Account {
+            id,
+            name,
+            label: None,
+            skin: None,
+            microsoft: None,
+        });
+        save(&accounts).await?;
+    }
+    Ok(accounts)
+}
+
+pub(crate) async fn rename(id: Uuid, label: String) -> Result<Vec<Account>, Errors> {
+    let mut accounts = stored().await?;
+    if let Some(account) = accounts.iter_mut().find(|account| account.id == id) {
+        account.label = Some(label).filter(|label| !label.is_empty() && *label != account.name);
+    }
+    save(&accounts).await?;
+    Ok(accounts)
+}
+
+pub(crate) async fn remove(id: Uuid) -> Result<Vec<Account>, Errors> {
+    let mut accounts = stored().await?;
+    accounts.retain(|account| account.id != id);
+    save(&accounts).await?;
+    Ok(accounts)
+}
+
+pub(crate) async fn head(account: Account) -> Option<Vec<u8>> {
+    let url = account.skin?;
+    let path = paths::CACHE
+        .clone()
+        .ok()?
+        .join("skins")
+        .join(format!("{}.png", account.id.simple()));
+    let skin = api::cached_get(&url, &path).await.ok()?;
+    let skin = image::load_from_memory_with_format(&skin, ImageFormat::Png)
+        .ok()?
+        .to_rgba8();
+    if skin.width() < 64 || skin.height() < 16 {
+        return None;
+    }
+
+    let mut face = imageops::crop_imm(&skin, 8, 8, 8, 8).to_image();
+    let hat = imageops::crop_imm(&skin, 40, 8, 8, 8).to_image();
+    imageops::overlay(&mut face, &hat, 0, 0);
+    let head: RgbaImage = imageops::resize(&face, HEAD_SIZE, HEAD_SIZE, imageops::Nearest);
+    Some(head.pixels().flat_map(|Rgba(pixel)| *pixel).collect())
+}
+
+pub(crate) async fn session(id: Uuid) -> Result<Session, Errors> {
+    let mut accounts = load().await?;
+    let account = accounts
+        .iter_mut()
+        .find(|account| account.id == id)
+        .ok_or(Errors::LoginExpired)?;
+    let Some(tokens) = &account.microsoft else {
+        return Ok(Session::offline(&account.name));
+    };
+    if tokens.expires_at > now() + EXPIRY_MARGIN {
+        return Ok(Session::new(
+            &account.name,
+            account.id,
+            &tokens.access_token,
+            &tokens.xuid,
+        ));
+    }
+
+    let token = microsoft::refresh_token(&tokens.refresh_token).await?;
+    let refreshed = authenticate(token.refresh_token, &token.access_token).await?;
+    account.name = refreshed.name;
+    account.skin = refreshed.skin;
+    account.microsoft = refreshed.microsoft;
+    let tokens = account.microsoft.as_ref().ok_or(Errors::LoginExpired)?;
+    let session = Session::new(
+        &account.name,
+        account.id,
+        &tokens.access_token,
+        &tokens.xuid,
+    );
+    save(&accounts).await?;
+    Ok(session)
+}
+
+async fn authenticate(refresh_token: String, microsoft_token: &str) -> Result<Account, Errors> {
+    let minecraft = microsoft::login_minecraft(microsoft_token).await?;
+    let profile = microsoft::profile(&minecraft.access_token).await?;
+    Ok(Account {
+        id: profile.id,
+        name: profile.name,
+        label: None,
+        skin: profile
+            .skins
+            .into_iter()
+            .find(|skin| skin.state == "ACTIVE")
+            .map(|skin| skin.url),
+        microsoft: Some(Tokens {
+            refresh_token,
+            access_token: minecraft.access_token,
+            expires_at: now() + minecraft.expires_in,
+            xuid: minecraft.xuid,
+        }),
+    })
+}
+
+async fn cipher() -> Result<Aes256Gcm, Errors> {
+    let path = paths::ACCOUNTS_KEY.clone()?;
+    migrate_key(&path).await?;
+    let key = if path.exists() {
+        let key = fs::read(&path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        if key.len() != 32 {
+            return Err(Errors::Encryption);
+        }
+        *Key::<Aes256Gcm>::from_slice(&key)
+    } else {
+        if paths::ACCOUNTS.clone()?.exists() {
+            return Err(Errors::Encryption);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|error| Errors::Io(error.kind()))?;
+        }
+        let key = Aes256Gcm::generate_key(OsRng);
+        fs::write(&path, key.as_slice())
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        restrict_permissions(&path).await?;
+        key
+    };
+    Ok(Aes256Gcm::new(&key))
+}
+
+async fn migrate_key(path: &std::path::Path) -> Result<(), Errors> {
+    let legacy = paths::LEGACY_ACCOUNTS_KEY.clone()?;
+    if path.exists() || !legacy.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+    }
+    fs::copy(&legacy, path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    restrict_permissions(path).await?;
+    fs::remove_file(&legacy)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+#[cfg(unix)]
+async fn restrict_permissions(path: &std::path::Path) -> Result<(), Errors> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+#[cfg(not(unix))]
+async fn restrict_permissions(_: &std::path::Path) -> Result<(), Errors> {
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
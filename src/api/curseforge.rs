@@ -0,0 +1,523 @@
+use std::collections::{HashMap, HashSet};
+
+use reqwest::RequestBuilder;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::fs;
+
+use crate::api::CLIENT;
+use crate::download::{Hash, Request};
+use crate::instance::{Instance, Loader, ModSource, Platform};
+use crate::{download, mods, store, Errors, CONFIG};
+
+pub(crate) const CURSEFORGE_URL: &str = "https://api.curseforge.com";
+
+pub(crate) const PAGE_SIZE: usize = 20;
+
+const MINECRAFT: u32 = 432;
+
+const MODS_CLASS: u32 = 6;
+
+const REQUIRED_DEPENDENCY: u8 = 3;
+
+const SHA1_ALGORITHM: u8 = 1;
+
+const MURMUR_SEED: u32 = 1;
+
+const MURMUR_MULTIPLIER: u32 = 0x5bd1_e995;
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct SearchResults {
+    pub(crate) data: Vec<Mod>,
+    pub(crate) pagination: Pagination,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Pagination {
+    pub(crate) index: usize,
+    pub(crate) total_count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Mod {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) summary: String,
+    pub(crate) download_count: f64,
+    #[serde(default)]
+    pub(crate) authors: Vec<Author>,
+    pub(crate) links: Links,
+    pub(crate) allow_mod_distribution: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Author {
+    pub(crate) name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Links {
+    pub(crate) website_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct File {
+    pub(crate) id: u64,
+    pub(crate) mod_id: u64,
+    pub(crate) display_name: String,
+    pub(crate) file_name: String,
+    pub(crate) release_type: u8,
+    pub(crate) file_date: String,
+    pub(crate) download_url: Option<String>,
+    #[serde(default)]
+    pub(crate) game_versions: Vec<String>,
+    #[serde(default)]
+    pub(crate) dependencies: Vec<FileDependency>,
+    pub(crate) file_fingerprint: u32,
+    #[serde(default)]
+    pub(crate) hashes: Vec<FileHash>,
+    pub(crate) file_length: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct FileHash {
+    pub(crate) value: String,
+    pub(crate) algo: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileDependency {
+    pub(crate) mod_id: u64,
+    pub(crate) relation_type: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FingerprintMatches {
+    exact_matches: Vec<FingerprintMatch>,
+}
+
+#[derive(Deserialize)]
+struct FingerprintMatch {
+    file: File,
+}
+
+impl Mod {
+    pub(crate) fn allows_distribution(&self) -> bool {
+        self.allow_mod_distribution != Some(false)
+    }
+}
+
+impl File {
+    pub(crate) fn release_type(&self) -> &'static str {
+        match self.release_type {
+            1 => "release",
+            2 => "beta",
+            _ => "alpha",
+        }
+    }
+}
+
+pub(crate) fn fingerprint(content: &[u8]) -> u32 {
+    let content = content
+        .iter()
+        .copied()
+        .filter(|byte| !matches!(byte, b'\t' | b'\n' | b'\r' | b' '))
+        .collect::<Vec<_>>();
+    let mut hash = MURMUR_SEED ^ content.len() as u32;
+    let mut chunks = content.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(MURMUR_MULTIPLIER);
+        k ^= k >> 24;
+        k = k.wrapping_mul(MURMUR_MULTIPLIER);
+        hash = hash.wrapping_mul(MURMUR_MULTIPLIER) ^ k;
+    }
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        for (i, byte) in rest.iter().enumerate() {
+            hash ^= u32::from(*byte) << (8 * i);
+        }
+        hash = hash.wrapping_mul(MURMUR_MULTIPLIER);
+    }
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(MURMUR_MULTIPLIER);
+    hash ^ (hash >> 15)
+}
+
+fn loader_types(loader: Loader) -> &'static [u8] {
+    match loader {
+        Loader::Vanilla => &[],
+        Loader::Forge => &[1],
+        Loader::Fabric => &[4],
+        Loader::Quilt => &[5, 4],
+        Loader::NeoForge => &[6],
+    }
+}
+
+pub(crate) async fn search(
+    query: String,
+    game_version: Option<String>,
+    loader: Option<Loader>,
+    offset: usize,
+) -> Result<SearchResults, Errors> {
+    let mut parameters = vec![
+        ("gameId", MINECRAFT.to_string()),
+        ("classId", MODS_CLASS.to_string()),
+        ("searchFilter", query),
+        ("sortField", String::from("2")),
+        ("sortOrder", String::from("desc")),
+        ("index", offset.to_string()),
+        ("pageSize", PAGE_SIZE.to_string()),
+    ];
+    if let Some(game_version) = game_version {
+        parameters.push(("gameVersion", game_version));
+    }
+    match loader.map(loader_types).unwrap_or_default() {
+        [] => {}
+        [loader_type] => parameters.push(("modLoaderType", loader_type.to_string())),
+        loader_types => parameters.push((
+            "modLoaderTypes",
+            serde_json::to_string(loader_types).map_err(|error| Errors::Json(error.to_string()))?,
+        )),
+    }
+    send(request("/v1/mods/search").await?.query(&parameters)).await
+}
+
+pub(crate) async fn files(
+    mod_id: u64,
+    game_version: String,
+    loader: Loader,
+) -> Result<Vec<File>, Errors> {
+    let mut loader_types = loader_types(loader)
+        .iter()
+        .map(|loader_type| Some(loader_type.to_string()))
+        .collect::<Vec<_>>();
+    if loader_types.is_empty() {
+        loader_types.push(None);
+    }
+    let mut files = Vec::<File>::new();
+    for loader_type in loader_types {
+        let mut parameters = vec![("gameVersion", game_version.clone())];
+        if let Some(loader_type) = loader_type {
+            parameters.push(("modLoaderType", loader_type));
+        }
+        for file in send::<Data<Vec<File>>>(
+            request(&format!("/v1/mods/{mod_id}/files"))
+                .await?
+                .query(&parameters),
+        )
+        .await?
+        .data
+        {
+            if !files.iter().any(|other| other.id == file.id) {
+                files.push(file);
+            }
+        }
+    }
+    files.sort_by(|a, b| b.file_date.cmp(&a.file_date));
+    Ok(files)
+}
+
+pub(crate) async fn file(mod_id: u64, file_id: u64) -> Result<File, Errors> {
+    send::<Data<File>>(request(&format!("/v1/mods/{mod_id}/files/{file_id}")).await?)
+        .await
+        .map(|file| file.data)
+}
+
+pub(crate) async fn project(mod_id: u64) -> Result<Mod, Errors> {
+    send::<Data<Mod>>(request(&format!("/v1/mods/{mod_id}")).await?)
+        .await
+        .map(|project| project.data)
+}
+
+pub(crate) async fn changelog(mod_id: u64, file_id: u64) -> Result<String, Errors> {
+    send::<Data<String>>(request(&format!("/v1/mods/{mod_id}/files/{file_id}/changelog")).await?)
+        .await
+        .map(|changelog| plain_text(&changelog.data))
+}
+
+pub(crate) async fn install(
+    mut instance: Instance,
+    project: Mod,
+    file: File,
+) -> (Instance, Result<Vec<String>, Errors>) {
+    let plan = match plan(&instance, project, file).await {
+        Ok(plan) => plan,
+        Err(error) => return (instance, Err(error)),
+    };
+    let mut installed = vec![];
+    for (project, file, url) in plan {
+        if let Err(error) = install_file(&mut instance, &project, &file, &url).await {
+            return (instance, Err(error));
+        }
+        installed.push(project.name);
+    }
+    (instance, Ok(installed))
+}
+
+async fn plan(
+    instance: &Instance,
+    project: Mod,
+    file: File,
+) -> Result<Vec<(Mod, File, String)>, Errors> {
+    let mut plan = vec![];
+    let mut seen = HashSet::new();
+    let mut queue = vec![(project, file)];
+    while let Some((project, file)) = queue.pop() {
+        if !seen.insert(project.id) {
+            continue;
+        }
+        for dependency in &file.dependencies {
+            let project_id = dependency.mod_id.to_string();
+            if dependency.relation_type != REQUIRED_DEPENDENCY
+                || seen.contains(&dependency.mod_id)
+                || instance
+                    .mod_sources()
+                    .values()
+                    .any(|source| source.project_id == project_id)
+            {
+                continue;
+            }
+            let dependency_file = newest(
+                files(
+                    dependency.mod_id,
+                    instance.minecraft_version().to_string(),
+                    instance.loader(),
+                )
+                .await?,
+            )
+            .ok_or(Errors::NoCompatibleVersion(project_id))?;
+            queue.push((self::project(dependency.mod_id).await?, dependency_file));
+        }
+
+        let url = match &file.download_url {
+            Some(url) if project.allows_distribution() => url.clone(),
+            _ => return Err(Errors::DistributionNotAllowed(project.name)),
+        };
+        if !mods::is_file_name(&file.file_name) {
+            return Err(Errors::InvalidFileName(file.file_name));
+        }
+        plan.push((project, file, url));
+    }
+    Ok(plan)
+}
+
+fn newest(files: Vec<File>) -> Option<File> {
+    files.into_iter().max_by(|a, b| {
+        (a.release_type == 1)
+            .cmp(&(b.release_type == 1))
+            .then_with(|| a.file_date.cmp(&b.file_date))
+    })
+}
+
+async fn install_file(
+    instance: &mut Instance,
+    project: &Mod,
+    file: &File,
+    url: &str,
+) -> Result<(), Errors> {
+    let mods_path = mods::mods_dir(instance.path());
+    let project_id = project.id.to_string();
+    let path = mods_path.join(&file.file_name);
+    let sha1 = file
+        .hashes
+        .iter()
+        .find(|hash| hash.algo == SHA1_ALGORITHM)
+        .map(|hash| Hash::Sha1(hash.value.clone()));
+    download::fetch(
+        Request::new(url, &path)
+            .hash(sha1)
+            .size(file.file_length)
+            .stored(),
+    )
+    .await?;
+    let content = fs::read(&path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    if fingerprint(&content) != file.file_fingerprint {
+        store::unlink(&path).await?;
+        return Err(Errors::ChecksumMismatch(file.file_name.clone()));
+    }
+    let previous = instance
+        .mod_sources()
+        .iter()
+        .filter(|(file_name, source)| {
+            source.platform == Platform::CurseForge
+                && source.project_id == project_id
+                && **file_name != file.file_name
+        })
+        .map(|(file_name, _)| file_name.clone())
+        .collect::<Vec<_>>();
+    for file_name in previous {
+        mods::remove(&mods_path, &file_name).await?;
+        instance.set_mod_source(&file_name, None).await?;
+    }
+    instance
+        .set_mod_source(
+            &file.file_name,
+            Some(ModSource {
+                platform: Platform::CurseForge,
+                project_id,
+                version_id: file.id.to_string(),
+                pinned: false,
+            }),
+        )
+        .await
+}
+
+pub(crate) async fn link(mut instance: Instance) -> Result<(Instance, usize), Errors> {
+    let mut jars = HashMap::new();
+    for info in mods::scan(mods::mods_dir(instance.path())).await? {
+        let file_name = mods::jar_name(&info.path);
+        if instance.mod_sources().contains_key(&file_name) {
+            continue;
+        }
+        let content = fs::read(&info.path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        jars.insert(fingerprint(&content), file_name);
+    }
+    if jars.is_empty() {
+        return Ok((instance, 0));
+    }
+
+    let matches = send::<Data<FingerprintMatches>>(
+        post(&format!("/v1/fingerprints/{MINECRAFT}"))
+            .await?
+            .json(&json!({ "fingerprints": jars.keys().collect::<Vec<_>>() })),
+    )
+    .await?
+    .data
+    .exact_matches;
+    let mut linked = 0;
+    for found in matches {
+        let Some(file_name) = jars.get(&found.file.file_fingerprint) else {
+            continue;
+        };
+        instance
+            .set_mod_source(
+                file_name,
+                Some(ModSource {
+                    platform: Platform::CurseForge,
+                    project_id: found.file.mod_id.to_string(),
+                    version_id: found.file.id.to_string(),
+                    pinned: false,
+                }),
+            )
+            .await?;
+        linked += 1;
+    }
+    Ok((instance, linked))
+}
+
+fn plain_text(html: &str) -> String {
+    let html = html
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p>", "\n")
+        .replace("<li>", "- ")
+        .replace("</li>", "\n");
+    let mut text = String::new();
+    let mut in_tag = false;
+    for character in html.chars() {
+        match character {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            character if !in_tag => text.push(character),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+async fn request(path: &str) -> Result<RequestBuilder, Errors> {
+    let (url, api_key) = settings().await?;
+    Ok(CLIENT
+        .get(format!("{url}{path}"))
+        .header("x-api-key", api_key))
+}
+
+async fn post(path: &str) -> Result<RequestBuilder, Errors> {
+    let (url, api_key) = settings().await?;
+    Ok(CLIENT
+        .post(format!("{url}{path}"))
+        .header("x-api-key", api_key))
+}
+
+async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, Errors> {
+    request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn settings() -> Result<(String, String), Errors> {
+    let config = CONFIG.lock().await;
+    let api_key = config
+        .curseforge_api_key
+        .clone()
+        .filter(|api_key| !api_key.is_empty())
+        .ok_or(Errors::MissingApiKey)?;
+    let url = config
+        .curseforge_url
+        .as_deref()
+        .unwrap_or(CURSEFORGE_URL)
+        .trim_end_matches('/')
+        .to_string();
+    Ok((url, api_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_vectors() {
+        assert_eq!(fingerprint(b""), 1540447798);
+        assert_eq!(fingerprint(b"a"), 626045324);
+        assert_eq!(fingerprint(b"ab"), 1692487918);
+        assert_eq!(fingerprint(b"abc"), 1621425345);
+        assert_eq!(fingerprint(b"abcd"), 3376380438);
+        assert_eq!(
+            fingerprint(b"The quick brown fox jumps over the lazy dog"),
+            3751777527
+        );
+    }
+
+    #[test]
+    fn fingerprint_ignores_whitespace() {
+        assert_eq!(fingerprint(b"hello world"), 2824650221);
+        assert_eq!(fingerprint(b"hello\r\n\tworld"), 2824650221);
+        assert_eq!(fingerprint(b" \t\r\n"), fingerprint(b""));
+    }
+
+    #[test]
+    fn quilt_accepts_fabric_files() {
+        assert_eq!(loader_types(Loader::Quilt), [5, 4]);
+        assert_eq!(loader_types(Loader::Fabric), [4]);
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::api::CLIENT;
+use crate::download::Request;
+use crate::instance::Loader;
+use crate::{download, paths, Errors, CONFIG};
+
+pub(crate) const FABRIC_URL: &str = "https://meta.fabricmc.net/v2";
+
+pub(crate) const QUILT_URL: &str = "https://meta.quiltmc.org/v3";
+
+pub(crate) const FORGE_URL: &str = "https://maven.minecraftforge.net";
+
+pub(crate) const NEOFORGE_URL: &str = "https://maven.neoforged.net/releases";
+
+pub(crate) async fn profile(
+    loader: Loader,
+    minecraft_version: &str,
+    loader_version: &str,
+) -> Result<Value, Errors> {
+    let url = base_url(loader).await;
+    CLIENT
+        .get(format!(
+            "{url}/versions/loader/{minecraft_version}/{loader_version}/profile/json"
+        ))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+pub(crate) async fn installer(
+    loader: Loader,
+    minecraft_version: &str,
+    loader_version: &str,
+) -> Result<PathBuf, Errors> {
+    let url = base_url(loader).await;
+    let (url, file_name) = match loader {
+        Loader::NeoForge => (
+            format!("{url}/net/neoforged/neoforge/{loader_version}"),
+            format!("neoforge-{loader_version}-installer.jar"),
+        ),
+        _ => (
+            format!("{url}/net/minecraftforge/forge/{minecraft_version}-{loader_version}"),
+            format!("forge-{minecraft_version}-{loader_version}-installer.jar"),
+        ),
+    };
+    let path = paths::CACHE.clone()?.join("installers").join(&file_name);
+    if path.exists() {
+        return Ok(path);
+    }
+
+    download::fetch(Request::new(format!("{url}/{file_name}"), &path)).await?;
+    Ok(path)
+}
+
+async fn base_url(loader: Loader) -> String {
+    let config = CONFIG.lock().await;
+    let url = match loader {
+        Loader::Vanilla | Loader::Fabric => config.fabric_url.as_deref().unwrap_or(FABRIC_URL),
+        Loader::Quilt => config.quilt_url.as_deref().unwrap_or(QUILT_URL),
+        Loader::Forge => config.forge_url.as_deref().unwrap_or(FORGE_URL),
+        Loader::NeoForge => config.neoforge_url.as_deref().unwrap_or(NEOFORGE_URL),
+    };
+    url.trim_end_matches('/').to_string()
+}
@@ -0,0 +1,296 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::api::CLIENT;
+use crate::{Errors, CONFIG};
+
+pub(crate) const MICROSOFT_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0";
+
+pub(crate) const XBOX_USER_URL: &str = "https://user.auth.xboxlive.com";
+
+pub(crate) const XSTS_URL: &str = "https://xsts.auth.xboxlive.com";
+
+pub(crate) const MINECRAFT_SERVICES_URL: &str = "https://api.minecraftservices.com";
+
+const SCOPE: &str = "XboxLive.signin offline_access";
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct DeviceCode {
+    device_code: String,
+    pub(crate) user_code: String,
+    pub(crate) verification_uri: String,
+    interval: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct MicrosoftToken {
+    pub(crate) access_token: String,
+    pub(crate) refresh_token: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MinecraftToken {
+    pub(crate) access_token: String,
+    pub(crate) expires_in: u64,
+    pub(crate) xuid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Profile {
+    pub(crate) id: Uuid,
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) skins: Vec<Skin>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Skin {
+    pub(crate) state: String,
+    pub(crate) url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OAuthError {
+    error: String,
+    error_description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XboxToken {
+    token: String,
+    display_claims: DisplayClaims,
+}
+
+#[derive(Debug, Deserialize)]
+struct DisplayClaims {
+    xui: Vec<XboxUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XboxUser {
+    uhs: String,
+    xid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XboxError {
+    x_err: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinecraftLogin {
+    access_token: String,
+    expires_in: u64,
+}
+
+pub(crate) async fn device_code() -> Result<DeviceCode, Errors> {
+    let (url, client_id) = microsoft().await?;
+    CLIENT
+        .post(format!("{url}/devicecode"))
+        .form(&[("client_id", client_id.as_str()), ("scope", SCOPE)])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+pub(crate) async fn poll_token(code: &DeviceCode) -> Result<MicrosoftToken, Errors> {
+    let (url, client_id) = microsoft().await?;
+    let mut interval = code.interval.max(1);
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        let result = request_token(
+            &url,
+            &[
+                ("grant_type", DEVICE_CODE_GRANT),
+                ("client_id", &client_id),
+                ("device_code", &code.device_code),
+            ],
+        )
+        .await;
+        match result {
+            Err(Errors::Login(error)) if error == "authorization_pending" => {}
+            Err(Errors::Login(error)) if error == "slow_down" => interval += 5,
+            result => return result,
+        }
+    }
+}
+
+pub(crate) async fn refresh_token(refresh_token: &str) -> Result<MicrosoftToken, Errors> {
+    let (url, client_id) = microsoft().await?;
+    request_token(
+        &url,
+        &[
+            ("grant_type", "refresh_token"),
+            ("client_id", &client_id),
+            ("refresh_token", refresh_token),
+            ("scope", SCOPE),
+        ],
+    )
+    .await
+}
+
+pub(crate) async fn login_minecraft(microsoft_token: &str) -> Result<MinecraftToken, Errors> {
+    let (xbox_user_url, xsts_url, minecraft_url) = {
+        let config = CONFIG.lock().await;
+        (
+            base_url(&config.xbox_user_url, XBOX_USER_URL),
+            base_url(&config.xsts_url, XSTS_URL),
+            base_url(&config.minecraft_services_url, MINECRAFT_SERVICES_URL),
+        )
+    };
+
+    let user_token = xbox_request(
+        &format!("{xbox_user_url}/user/authenticate"),
+        json!({
+            "Properties": {
+                "AuthMethod": "RPS",
+                "SiteName": "user.auth.xboxlive.com",
+                "RpsTicket": format!("d={microsoft_token}"),
+            },
+            "RelyingParty": "http://auth.xboxlive.com",
+            "TokenType": "JWT",
+        }),
+    )
+    .await?;
+    let xsts_token = xbox_request(
+        &format!("{xsts_url}/xsts/authorize"),
+        json!({
+            "Properties": {
+                "SandboxId": "RETAIL",
+                "UserTokens": [user_token.token],
+            },
+            "RelyingParty": "rp://api.minecraftservices.com/",
+            "TokenType": "JWT",
+        }),
+    )
+    .await?;
+    let user = xsts_token
+        .display_claims
+        .xui
+        .into_iter()
+        .next()
+        .ok_or_else(|| Errors::Login(String::from("Xbox Live returned no user")))?;
+
+    let login = CLIENT
+        .post(format!("{minecraft_url}/authentication/login_with_xbox"))
+        .json(&json!({
+            "identityToken": format!("XBL3.0 x={};{}", user.uhs, xsts_token.token),
+        }))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json::<MinecraftLogin>()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))?;
+
+    Ok(MinecraftToken {
+        access_token: login.access_token,
+        expires_in: login.expires_in,
+        xuid: user.xid.unwrap_or_else(|| String::from("0")),
+    })
+}
+
+pub(crate) async fn profile(minecraft_token: &str) -> Result<Profile, Errors> {
+    let url = base_url(
+        &CONFIG.lock().await.minecraft_services_url,
+        MINECRAFT_SERVICES_URL,
+    );
+    let response = CLIENT
+        .get(format!("{url}/minecraft/profile"))
+        .bearer_auth(minecraft_token)
+        .send()
+        .await
+        .map_err(|error| Errors::Http(error.to_string()))?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(Errors::NoMinecraftLicense);
+    }
+    response
+        .error_for_status()
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn request_token(url: &str, form: &[(&str, &str)]) -> Result<MicrosoftToken, Errors> {
+    let response = CLIENT
+        .post(format!("{url}/token"))
+        .form(form)
+        .send()
+        .await
+        .map_err(|error| Errors::Http(error.to_string()))?;
+    if response.status().is_success() {
+        return response
+            .json()
+            .await
+            .map_err(|error| Errors::Json(error.to_string()));
+    }
+
+    let error = response
+        .json::<OAuthError>()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))?;
+    Err(match error.error.as_str() {
+        "authorization_declined" => Errors::LoginDeclined,
+        "expired_token" => Errors::LoginExpired,
+        "invalid_grant" => Errors::LoginExpired,
+        "authorization_pending" | "slow_down" => Errors::Login(error.error),
+        _ => Errors::Login(error.error_description.unwrap_or(error.error)),
+    })
+}
+
+async fn xbox_request(url: &str, body: Value) -> Result<XboxToken, Errors> {
+    let response = CLIENT
+        .post(url)
+        .header("Accept", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|error| Errors::Http(error.to_string()))?;
+    if response.status() == StatusCode::UNAUTHORIZED {
+        let error = response
+            .json::<XboxError>()
+            .await
+            .map_err(|error| Errors::Json(error.to_string()))?;
+        return Err(match error.x_err {
+            2148916233 => Errors::NoXboxAccount,
+            2148916235..=2148916238 => Errors::XboxRestricted(error.x_err),
+            code => Errors::Login(format!("Xbox Live error {code}")),
+        });
+    }
+    response
+        .error_for_status()
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn microsoft() -> Result<(String, String), Errors> {
+    let config = CONFIG.lock().await;
+    let client_id = config
+        .microsoft_client_id
+        .clone()
+        .ok_or(Errors::MissingClientId)?;
+    Ok((base_url(&config.microsoft_url, MICROSOFT_URL), client_id))
+}
+
+fn base_url(url: &Option<String>, default: &str) -> String {
+    url.as_deref()
+        .unwrap_or(default)
+        .trim_end_matches('/')
+        .to_string()
+}
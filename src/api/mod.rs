@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tokio::fs;
+
+use crate::Errors;
+
+pub(crate) mod curseforge;
+pub(crate) mod loader;
+pub(crate) mod microsoft;
+pub(crate) mod modrinth;
+pub(crate) mod mojang;
+
+pub(crate) static CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .user_agent(concat!("aethon/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+});
+
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheEntry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub(crate) async fn cached_get(url: &str, path: &Path) -> Result<Vec<u8>, Errors> {
+    let entry_path = path.with_extension("cache.json");
+    let cached = fs::read(path).await.ok();
+    let entry = match &cached {
+        Some(_) => fs::read_to_string(&entry_path)
+            .await
+            .ok()
+            .and_then(|entry| serde_json::from_str::<CacheEntry>(&entry).ok())
+            .unwrap_or_default(),
+        None => CacheEntry::default(),
+    };
+
+    let mut request = CLIENT.get(url);
+    if let Some(etag) = &entry.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &entry.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+    let response = match request.send().await.and_then(|response| {
+        if response.status() == StatusCode::NOT_MODIFIED {
+            Ok(response)
+        } else {
+            response.error_for_status()
+        }
+    }) {
+        Ok(response) => response,
+        Err(error) => return cached.ok_or(Errors::Http(error.to_string())),
+    };
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some(cached) = cached {
+            return Ok(cached);
+        }
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let entry = CacheEntry {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let body = response
+        .bytes()
+        .await
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .to_vec();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+    }
+    fs::write(path, &body)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    fs::write(
+        entry_path,
+        serde_json::to_string(&entry).map_err(|error| Errors::Json(error.to_string()))?,
+    )
+    .await
+    .map_err(|error| Errors::Io(error.kind()))?;
+
+    Ok(body)
+}
@@ -0,0 +1,555 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::fs;
+
+use crate::api::CLIENT;
+use crate::download::{Hash, Request};
+use crate::instance::{Instance, Loader, ModSource, Platform};
+use crate::{download, hash, mods, Errors, CONFIG};
+
+pub(crate) const MODRINTH_URL: &str = "https://api.modrinth.com/v2";
+
+pub(crate) const PAGE_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Facets {
+    pub(crate) game_version: Option<String>,
+    pub(crate) loader: Option<Loader>,
+    pub(crate) category: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct SearchResults {
+    pub(crate) hits: Vec<SearchHit>,
+    pub(crate) offset: usize,
+    pub(crate) total_hits: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct SearchHit {
+    pub(crate) project_id: String,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    pub(crate) author: String,
+    pub(crate) downloads: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Project {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    #[serde(default)]
+    pub(crate) categories: Vec<String>,
+    pub(crate) downloads: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Version {
+    pub(crate) id: String,
+    pub(crate) project_id: String,
+    pub(crate) name: String,
+    pub(crate) version_number: String,
+    pub(crate) changelog: Option<String>,
+    #[serde(default)]
+    pub(crate) game_versions: Vec<String>,
+    #[serde(default)]
+    pub(crate) loaders: Vec<String>,
+    pub(crate) version_type: String,
+    pub(crate) date_published: String,
+    pub(crate) files: Vec<VersionFile>,
+    #[serde(default)]
+    pub(crate) dependencies: Vec<VersionDependency>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct VersionFile {
+    pub(crate) url: String,
+    pub(crate) filename: String,
+    #[serde(default)]
+    pub(crate) primary: bool,
+    #[serde(default)]
+    pub(crate) hashes: HashMap<String, String>,
+    pub(crate) size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct VersionDependency {
+    pub(crate) version_id: Option<String>,
+    pub(crate) project_id: Option<String>,
+    pub(crate) dependency_type: DependencyType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DependencyType {
+    Required,
+    Optional,
+    Incompatible,
+    Embedded,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Algorithm {
+    Sha1,
+    Sha512,
+}
+
+#[derive(Deserialize)]
+struct Category {
+    name: String,
+    project_type: String,
+}
+
+impl VersionFile {
+    pub(crate) fn request(&self, path: &Path) -> Request {
+        Request::new(&self.url, path)
+            .hash(self.hashes.get("sha1").cloned().map(Hash::Sha1))
+            .hash(self.hashes.get("sha512").cloned().map(Hash::Sha512))
+            .size(self.size)
+            .stored()
+    }
+}
+
+impl Version {
+    pub(crate) fn primary_file(&self) -> Option<&VersionFile> {
+        self.files
+            .iter()
+            .find(|file| file.primary)
+            .or(self.files.first())
+    }
+}
+
+pub(crate) fn loaders(loader: Loader) -> &'static [&'static str] {
+    match loader {
+        Loader::Vanilla => &[],
+        Loader::Fabric => &["fabric"],
+        Loader::Quilt => &["quilt", "fabric"],
+        Loader::Forge => &["forge"],
+        Loader::NeoForge => &["neoforge"],
+    }
+}
+
+pub(crate) async fn search(
+    query: String,
+    facets: Facets,
+    offset: usize,
+) -> Result<SearchResults, Errors> {
+    let mut groups = vec![vec![String::from("project_type:mod")]];
+    if let Some(game_version) = &facets.game_version {
+        groups.push(vec![format!("versions:{game_version}")]);
+    }
+    if let Some(loader) = facets.loader.filter(|loader| !loaders(*loader).is_empty()) {
+        groups.push(
+            loaders(loader)
+                .iter()
+                .map(|loader| format!("categories:{loader}"))
+                .collect(),
+        );
+    }
+    if let Some(category) = &facets.category {
+        groups.push(vec![format!("categories:{category}")]);
+    }
+    get(
+        "/search",
+        &[
+            ("query", query),
+            (
+                "facets",
+                serde_json::to_string(&groups).map_err(|error| Errors::Json(error.to_string()))?,
+            ),
+            ("limit", PAGE_SIZE.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
+    .await
+}
+
+pub(crate) async fn categories() -> Result<Vec<String>, Errors> {
+    let mut categories = get::<Vec<Category>>("/tag/category", &[])
+        .await?
+        .into_iter()
+        .filter(|category| category.project_type == "mod")
+        .map(|category| category.name)
+        .collect::<Vec<_>>();
+    categories.sort();
+    categories.dedup();
+    Ok(categories)
+}
+
+pub(crate) async fn project(id: String) -> Result<Project, Errors> {
+    get(&format!("/project/{id}"), &[]).await
+}
+
+pub(crate) async fn version(id: &str) -> Result<Version, Errors> {
+    get(&format!("/version/{id}"), &[]).await
+}
+
+pub(crate) async fn versions(
+    project_id: String,
+    game_version: String,
+    loader: Loader,
+) -> Result<Vec<Version>, Errors> {
+    let loaders =
+        serde_json::to_string(loaders(loader)).map_err(|error| Errors::Json(error.to_string()))?;
+    let game_versions =
+        serde_json::to_string(&[game_version]).map_err(|error| Errors::Json(error.to_string()))?;
+    get(
+        &format!("/project/{project_id}/version"),
+        &[("loaders", loaders), ("game_versions", game_versions)],
+    )
+    .await
+}
+
+pub(crate) async fn install(
+    mut instance: Instance,
+    version: Version,
+) -> (Instance, Result<Vec<String>, Errors>) {
+    let result = install_into(&mut instance, version).await;
+    (instance, result)
+}
+
+async fn install_into(instance: &mut Instance, version: Version) -> Result<Vec<String>, Errors> {
+    let mods_path = mods::mods_dir(instance.path());
+    let unlinked = unlinked(instance)
+        .await?
+        .into_iter()
+        .map(|(jar_name, version)| (jar_name, version.project_id))
+        .collect::<Vec<_>>();
+    let is_installed = |instance: &Instance, project_id: &str| {
+        unlinked.iter().any(|(_, unlinked)| unlinked == project_id)
+            || instance
+                .mod_sources()
+                .values()
+                .any(|source| source.project_id == project_id)
+    };
+    let mut installed = vec![];
+    let mut seen = HashSet::new();
+    let root = version.project_id.clone();
+    let mut queue = vec![version];
+    while let Some(version) = queue.pop() {
+        if !seen.insert(version.project_id.clone())
+            || (version.project_id != root && is_installed(instance, &version.project_id))
+        {
+            continue;
+        }
+        for dependency in &version.dependencies {
+            if dependency.dependency_type != DependencyType::Required
+                || dependency.project_id.as_ref().is_some_and(|project_id| {
+                    seen.contains(project_id) || is_installed(instance, project_id)
+                })
+            {
+                continue;
+            }
+            if let Some(dependency_version) =
+                resolve(dependency, instance.minecraft_version(), instance.loader()).await?
+            {
+                queue.push(dependency_version);
+            }
+        }
+
+        let file = version
+            .primary_file()
+            .ok_or_else(|| Errors::NoCompatibleVersion(version.name.clone()))?;
+        if !mods::is_file_name(&file.filename) {
+            return Err(Errors::InvalidFileName(file.filename.clone()));
+        }
+        download::fetch(file.request(&mods_path.join(&file.filename))).await?;
+        let previous = instance
+            .mod_sources()
+            .iter()
+            .filter(|(file_name, source)| {
+                source.platform == Platform::Modrinth
+                    && source.project_id == version.project_id
+                    && **file_name != file.filename
+            })
+            .map(|(file_name, _)| file_name.clone())
+            .collect::<Vec<_>>();
+        for file_name in previous {
+            mods::remove(&mods_path, &file_name).await?;
+            instance.set_mod_source(&file_name, None).await?;
+        }
+        for (file_name, _) in unlinked.iter().filter(|(file_name, project_id)| {
+            *project_id == version.project_id && *file_name != file.filename
+        }) {
+            mods::remove(&mods_path, file_name).await?;
+        }
+        instance
+            .set_mod_source(
+                &file.filename,
+                Some(ModSource {
+                    platform: Platform::Modrinth,
+                    project_id: version.project_id.clone(),
+                    version_id: version.id.clone(),
+                    pinned: false,
+                }),
+            )
+            .await?;
+        installed.push(version.name.clone());
+    }
+    Ok(installed)
+}
+
+async fn resolve(
+    dependency: &VersionDependency,
+    game_version: &str,
+    loader: Loader,
+) -> Result<Option<Version>, Errors> {
+    match (&dependency.version_id, &dependency.project_id) {
+        (Some(version_id), _) => Ok(Some(version(version_id).await?)),
+        (None, Some(project_id)) => {
+            newest(versions(project_id.clone(), game_version.to_string(), loader).await?)
+                .map(Some)
+                .ok_or_else(|| Errors::NoCompatibleVersion(project_id.clone()))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+fn newest(versions: Vec<Version>) -> Option<Version> {
+    versions.into_iter().max_by(|a, b| {
+        (a.version_type == "release")
+            .cmp(&(b.version_type == "release"))
+            .then_with(|| a.date_published.cmp(&b.date_published))
+    })
+}
+
+pub(crate) async fn link(mut instance: Instance) -> Result<(Instance, usize), Errors> {
+    let mut linked = 0;
+    for (jar_name, version) in unlinked(&instance).await? {
+        instance
+            .set_mod_source(
+                &jar_name,
+                Some(ModSource {
+                    platform: Platform::Modrinth,
+                    project_id: version.project_id.clone(),
+                    version_id: version.id.clone(),
+                    pinned: false,
+                }),
+            )
+            .await?;
+        linked += 1;
+    }
+    Ok((instance, linked))
+}
+
+async fn unlinked(instance: &Instance) -> Result<Vec<(String, Version)>, Errors> {
+    let mut jars = vec![];
+    for info in mods::scan(mods::mods_dir(instance.path())).await? {
+        let jar_name = mods::jar_name(&info.path);
+        if instance.mod_sources().contains_key(&jar_name) {
+            continue;
+        }
+        let content = fs::read(&info.path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        jars.push((jar_name, hash::sha1(&content), hash::sha512(&content)));
+    }
+
+    let mut found = vec![];
+    for algorithm in [Algorithm::Sha512, Algorithm::Sha1] {
+        let hashes = jars
+            .iter()
+            .map(|(_, sha1, sha512)| match algorithm {
+                Algorithm::Sha1 => sha1.clone(),
+                Algorithm::Sha512 => sha512.clone(),
+            })
+            .collect::<Vec<_>>();
+        if hashes.is_empty() {
+            break;
+        }
+        let versions = post::<HashMap<String, Version>>(
+            "/version_files",
+            &json!({ "hashes": hashes, "algorithm": algorithm }),
+        )
+        .await?;
+        let mut unmatched = vec![];
+        for (jar_name, sha1, sha512) in jars {
+            let hash = match algorithm {
+                Algorithm::Sha1 => &sha1,
+                Algorithm::Sha512 => &sha512,
+            };
+            match versions.get(hash) {
+                Some(version) => found.push((jar_name, version.clone())),
+                None => unmatched.push((jar_name, sha1, sha512)),
+            }
+        }
+        jars = unmatched;
+    }
+    Ok(found)
+}
+
+async fn get<T: DeserializeOwned>(path: &str, query: &[(&str, String)]) -> Result<T, Errors> {
+    let url = base_url().await;
+    CLIENT
+        .get(format!("{url}{path}"))
+        .query(query)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn post<T: DeserializeOwned>(path: &str, body: &serde_json::Value) -> Result<T, Errors> {
+    let url = base_url().await;
+    CLIENT
+        .post(format!("{url}{path}"))
+        .json(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| Errors::Http(error.to_string()))?
+        .json()
+        .await
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn base_url() -> String {
+    CONFIG
+        .lock()
+        .await
+        .modrinth_url
+        .as_deref()
+        .unwrap_or(MODRINTH_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use once_cell::sync::Lazy;
+    use serde_json::Value;
+
+    use super::*;
+
+    static SERVER: Lazy<String> = Lazy::new(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                let mut request = String::new();
+                let mut reader = BufReader::new(&stream);
+                while reader.read_line(&mut request).is_ok_and(|read| read > 2) {}
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = match route(path.split('?').next().unwrap_or_default()) {
+                    Some(body) => ("200 OK", body.to_string()),
+                    None => ("404 Not Found", String::from("{}")),
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        format!("http://{address}")
+    });
+
+    fn route(path: &str) -> Option<Value> {
+        match path {
+            "/project/lib/version" => Some(json!([
+                version("lib-old", "lib", "release", "2023-01-01T00:00:00Z"),
+                version("lib-beta", "lib", "beta", "2024-06-01T00:00:00Z"),
+                version("lib-new", "lib", "release", "2024-01-01T00:00:00Z"),
+            ])),
+            "/project/snapshot/version" => Some(json!([
+                version(
+                    "snapshot-alpha",
+                    "snapshot",
+                    "alpha",
+                    "2024-01-01T00:00:00Z"
+                ),
+                version("snapshot-beta", "snapshot", "beta", "2024-02-01T00:00:00Z"),
+            ])),
+            "/project/missing/version" => Some(json!([])),
+            "/version/pinned" => Some(version("pinned", "lib", "beta", "2022-01-01T00:00:00Z")),
+            _ => None,
+        }
+    }
+
+    fn version(id: &str, project_id: &str, version_type: &str, date: &str) -> Value {
+        json!({
+            "id": id,
+            "project_id": project_id,
+            "name": id,
+            "version_number": id,
+            "version_type": version_type,
+            "date_published": date,
+            "files": [{ "url": format!("https://cdn.example/{id}.jar"), "filename": format!("{id}.jar") }],
+        })
+    }
+
+    fn dependency(version_id: Option<&str>, project_id: Option<&str>) -> VersionDependency {
+        VersionDependency {
+            version_id: version_id.map(str::to_string),
+            project_id: project_id.map(str::to_string),
+            dependency_type: DependencyType::Required,
+        }
+    }
+
+    async fn resolve_from_mock(dependency: VersionDependency) -> Result<Option<Version>, Errors> {
+        CONFIG.lock().await.modrinth_url = Some(SERVER.clone());
+        resolve(&dependency, "1.20.1", Loader::Fabric).await
+    }
+
+    #[tokio::test]
+    async fn resolves_newest_release() {
+        let version = resolve_from_mock(dependency(None, Some("lib")))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(version.id, "lib-new");
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_newest_pre_release() {
+        let version = resolve_from_mock(dependency(None, Some("snapshot")))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(version.id, "snapshot-beta");
+    }
+
+    #[tokio::test]
+    async fn resolves_pinned_versions() {
+        let version = resolve_from_mock(dependency(Some("pinned"), Some("lib")))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(version.id, "pinned");
+    }
+
+    #[tokio::test]
+    async fn reports_missing_versions() {
+        let result = resolve_from_mock(dependency(None, Some("missing"))).await;
+        assert!(
+            matches!(result, Err(Errors::NoCompatibleVersion(project)) if project == "missing")
+        );
+        assert!(resolve_from_mock(dependency(None, None))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_file_names_with_paths() {
+        assert!(mods::is_file_name("sodium-0.5.3.jar"));
+        assert!(!mods::is_file_name("../sodium.jar"));
+        assert!(!mods::is_file_name("config/sodium.jar"));
+        assert!(!mods::is_file_name("/tmp/sodium.jar"));
+        assert!(!mods::is_file_name(".."));
+        assert!(!mods::is_file_name(""));
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::api::cached_get;
+use crate::{paths, Errors, CONFIG};
+
+pub(crate) const DEFAULT_URL: &str = "https://piston-meta.mojang.com";
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct VersionManifest {
+    pub(crate) latest: Latest,
+    pub(crate) versions: Vec<VersionEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Latest {
+    pub(crate) release: String,
+    pub(crate) snapshot: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct VersionEntry {
+    pub(crate) id: String,
+    #[serde(rename = "type")]
+    pub(crate) version_type: VersionType,
+    pub(crate) url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum VersionType {
+    Release,
+    Snapshot,
+    OldBeta,
+    OldAlpha,
+}
+
+impl VersionType {
+    pub(crate) const ALL: [VersionType; 4] = [
+        VersionType::Release,
+        VersionType::Snapshot,
+        VersionType::OldBeta,
+        VersionType::OldAlpha,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            VersionType::Release => "Release",
+            VersionType::Snapshot => "Snapshot",
+            VersionType::OldBeta => "Old beta",
+            VersionType::OldAlpha => "Old alpha",
+        }
+    }
+}
+
+impl VersionManifest {
+    pub(crate) fn filter<'a>(
+        &'a self,
+        version_types: &'a [VersionType],
+    ) -> impl Iterator<Item = &'a VersionEntry> {
+        self.versions
+            .iter()
+            .filter(|version| version_types.contains(&version.version_type))
+    }
+
+    pub(crate) fn find(&self, id: &str) -> Option<&VersionEntry> {
+        let id = match id {
+            "latest-release" => &self.latest.release,
+            "latest-snapshot" => &self.latest.snapshot,
+            id => id,
+        };
+        self.versions.iter().find(|version| version.id == id)
+    }
+}
+
+pub(crate) async fn manifest() -> Result<VersionManifest, Errors> {
+    let url = base_url().await;
+    let body = cached_get(
+        &format!("{url}/mc/game/version_manifest_v2.json"),
+        &paths::CACHE
+            .clone()?
+            .join("mojang/version_manifest_v2.json"),
+    )
+    .await?;
+    serde_json::from_slice(&body).map_err(|error| Errors::Json(error.to_string()))
+}
+
+pub(crate) async fn version(entry: &VersionEntry) -> Result<Value, Errors> {
+    let url = base_url().await;
+    let body = cached_get(
+        &entry.url.replacen(DEFAULT_URL, &url, 1),
+        &paths::CACHE
+            .clone()?
+            .join("mojang/versions")
+            .join(format!("{}.json", entry.id)),
+    )
+    .await?;
+    serde_json::from_slice(&body).map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn base_url() -> String {
+    CONFIG
+        .lock()
+        .await
+        .mojang_url
+        .clone()
+        .unwrap_or_else(|| DEFAULT_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
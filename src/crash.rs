@@ -0,0 +1,502 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+use tokio::fs;
+use zip::ZipArchive;
+
+use crate::instance::Instance;
+use crate::{log, mods, paths, Errors};
+
+const MAX_MIXIN_FAILURES: usize = 20;
+
+const IGNORED_PACKAGES: [&str; 9] = [
+    "java.",
+    "javax.",
+    "jdk.",
+    "sun.",
+    "net.minecraft.",
+    "com.mojang.",
+    "org.spongepowered.",
+    "cpw.mods.",
+    "net.fabricmc.loader.",
+];
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Report {
+    pub(crate) crash_report: Option<PathBuf>,
+    pub(crate) description: Option<String>,
+    pub(crate) exceptions: Vec<Exception>,
+    pub(crate) mixin_failures: Vec<String>,
+    pub(crate) suspects: Vec<Suspect>,
+    pub(crate) findings: Vec<Finding>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Exception {
+    pub(crate) class: String,
+    pub(crate) message: String,
+    pub(crate) frames: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Suspect {
+    pub(crate) jar: String,
+    pub(crate) reason: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Finding {
+    pub(crate) title: String,
+    pub(crate) advice: String,
+    pub(crate) line: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Rule {
+    title: String,
+    patterns: Vec<String>,
+    advice: String,
+}
+
+#[derive(Debug, Default)]
+struct Jar {
+    packages: HashSet<String>,
+    configs: BTreeSet<String>,
+}
+
+pub(crate) async fn analyze(
+    instance: Instance,
+    since: Option<SystemTime>,
+) -> Result<Report, Errors> {
+    let crash_report = newest_crash_report(instance.path(), since);
+    let crash_text = match &crash_report {
+        Some(path) => fs::read_to_string(path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?,
+        None => String::new(),
+    };
+    let log_text = fs::read_to_string(log::latest(instance.path()))
+        .await
+        .unwrap_or_default();
+
+    let description = crash_text
+        .lines()
+        .find_map(|line| line.strip_prefix("Description: "))
+        .map(str::to_string);
+    let exceptions = match exception_chains(&crash_text).into_iter().next() {
+        Some(exceptions) => exceptions,
+        None => exception_chains(&log_text).pop().unwrap_or_default(),
+    };
+    let mixin_failures = mixin_failures(&crash_text, &log_text);
+
+    let mods_path = mods::mods_dir(instance.path());
+    let jars = tokio::task::spawn_blocking(move || index_jars(&mods_path))
+        .await
+        .unwrap_or_default();
+    let suspects = suspects(&jars, &exceptions, &mixin_failures);
+
+    let mut rules = bundled_rules();
+    rules.extend(custom_rules().await);
+    let findings = findings(&rules, &[&crash_text, &log_text]);
+
+    Ok(Report {
+        crash_report,
+        description,
+        exceptions,
+        mixin_failures,
+        suspects,
+        findings,
+    })
+}
+
+fn newest_crash_report(instance_path: &Path, since: Option<SystemTime>) -> Option<PathBuf> {
+    std::fs::read_dir(instance_path.join("crash-reports"))
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|extension| extension == "txt")
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .filter(|(modified, _)| since.is_none_or(|since| *modified >= since))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn exception_chains(text: &str) -> Vec<Vec<Exception>> {
+    let mut chains = vec![];
+    let mut exceptions: Vec<Exception> = vec![];
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(frame) = trimmed.strip_prefix("at ") {
+            if let Some(exception) = exceptions.last_mut() {
+                exception.frames.push(frame.to_string());
+            }
+            continue;
+        }
+        if trimmed.starts_with("...") && trimmed.ends_with("more") {
+            continue;
+        }
+        let caused_by = trimmed.strip_prefix("Caused by: ");
+        match exception_header(caused_by.unwrap_or(trimmed)) {
+            Some(exception) if exceptions.is_empty() || caused_by.is_some() => {
+                exceptions.push(exception)
+            }
+            Some(exception) => chains.push(std::mem::replace(&mut exceptions, vec![exception])),
+            None if !exceptions.is_empty() => chains.push(std::mem::take(&mut exceptions)),
+            None => {}
+        }
+    }
+    if !exceptions.is_empty() {
+        chains.push(exceptions);
+    }
+    chains
+}
+
+fn exception_header(line: &str) -> Option<Exception> {
+    let (class, message) = match line.split_once(": ") {
+        Some((class, message)) => (class, message),
+        None => (line.trim_end_matches(':'), ""),
+    };
+    let simple_name = class.rsplit('.').next()?;
+    let is_throwable = ["Exception", "Error", "Throwable"]
+        .iter()
+        .any(|suffix| simple_name.ends_with(suffix));
+    let is_class = class.contains('.')
+        && class
+            .chars()
+            .all(|char| char.is_alphanumeric() || matches!(char, '.' | '_' | '$'));
+    (is_throwable && is_class).then(|| Exception {
+        class: class.to_string(),
+        message: message.to_string(),
+        frames: vec![],
+    })
+}
+
+fn mixin_failures(crash_text: &str, log_text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    crash_text
+        .lines()
+        .chain(log_text.lines())
+        .map(str::trim)
+        .filter(|line| {
+            line.contains("Mixin")
+                && ["fail", "error", "exception", "invalid"]
+                    .iter()
+                    .any(|keyword| line.to_lowercase().contains(keyword))
+        })
+        .filter(|line| seen.insert(line.to_string()))
+        .take(MAX_MIXIN_FAILURES)
+        .map(str::to_string)
+        .collect()
+}
+
+fn index_jars(mods_path: &Path) -> BTreeMap<String, Jar> {
+    let Ok(entries) = std::fs::read_dir(mods_path) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "jar"))
+        .filter_map(|path| {
+            let archive = ZipArchive::new(File::open(&path).ok()?).ok()?;
+            let mut jar = Jar::default();
+            for name in archive.file_names() {
+                if let Some(class) = name.strip_suffix(".class") {
+                    if let Some((package, _)) = class.rsplit_once('/') {
+                        jar.packages.insert(package.replace('/', "."));
+                    }
+                }
+            }
+            if let Ok(info) = mods::inspect(&path) {
+                jar.configs.extend(info.mixins);
+                jar.configs
+                    .extend(info.bundled.into_iter().flat_map(|bundled| bundled.mixins));
+            }
+            let file_name = path.file_name()?.to_string_lossy().to_string();
+            Some((file_name, jar))
+        })
+        .collect()
+}
+
+fn suspects(
+    jars: &BTreeMap<String, Jar>,
+    exceptions: &[Exception],
+    mixin_failures: &[String],
+) -> Vec<Suspect> {
+    let mut frames = HashMap::<&str, usize>::new();
+    for frame in exceptions.iter().flat_map(|exception| &exception.frames) {
+        let method = frame.split('(').next().unwrap_or(frame);
+        let method = method.rsplit('/').next().unwrap_or(method);
+        let Some((class, _)) = method.rsplit_once('.') else {
+            continue;
+        };
+        let Some((package, _)) = class.rsplit_once('.') else {
+            continue;
+        };
+        if IGNORED_PACKAGES
+            .iter()
+            .any(|ignored| package.starts_with(ignored))
+        {
+            continue;
+        }
+        let jar = jars
+            .iter()
+            .find(|(_, jar)| jar.packages.contains(package))
+            .map(|(name, _)| name.as_str());
+        if let Some(jar) = jar {
+            *frames.entry(jar).or_default() += 1;
+        }
+    }
+
+    let mut suspects = frames
+        .into_iter()
+        .map(|(jar, count)| {
+            (
+                count,
+                Suspect {
+                    jar: jar.to_string(),
+                    reason: format!("{count} stack frame(s) in the crash"),
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    suspects.sort_by(|(a, first), (b, second)| b.cmp(a).then(first.jar.cmp(&second.jar)));
+    let mut suspects = suspects
+        .into_iter()
+        .map(|(_, suspect)| suspect)
+        .collect::<Vec<_>>();
+
+    for (name, jar) in jars {
+        if let Some(config) = jar.configs.iter().find(|config| {
+            mixin_failures
+                .iter()
+                .any(|failure| failure.contains(config.as_str()))
+        }) {
+            suspects.push(Suspect {
+                jar: name.clone(),
+                reason: format!("mixin config {config} failed to apply"),
+            });
+        }
+    }
+    suspects
+}
+
+fn findings(rules: &[Rule], texts: &[&str]) -> Vec<Finding> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let line = texts.iter().flat_map(|text| text.lines()).find(|line| {
+                rule.patterns
+                    .iter()
+                    .any(|pattern| line.contains(pattern.as_str()))
+            })?;
+            Some(Finding {
+                title: rule.title.clone(),
+                advice: rule.advice.clone(),
+                line: line.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+async fn custom_rules() -> Vec<Rule> {
+    let Ok(path) = paths::CRASH_RULES.clone() else {
+        return vec![];
+    };
+    fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|rules| serde_json::from_str(&rules).ok())
+        .unwrap_or_default()
+}
+
+fn bundled_rules() -> Vec<Rule> {
+    let rule = |title: &str, patterns: &[&str], advice: &str| Rule {
+        title: title.to_string(),
+        patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+        advice: advice.to_string(),
+    };
+    vec![
+        rule(
+            "Wrong Java version",
+            &[
+                "UnsupportedClassVersionError",
+                "has been compiled by a more recent version of the Java Runtime",
+                "Unsupported class file major version",
+            ],
+            "The game or a mod needs a newer Java version, pick a newer runtime for this instance.",
+        ),
+        rule(
+            "Out of memory",
+            &[
+                "java.lang.OutOfMemoryError",
+                "Could not reserve enough space",
+            ],
+            "The game ran out of memory, allocate more memory or remove heavy mods.",
+        ),
+        rule(
+            "Missing dependency",
+            &[
+                "Missing or unsupported mandatory dependencies",
+                "requires any version of",
+                "requires version",
+                "Could not find required mod",
+                "is missing the following mods",
+            ],
+            "A mod requires another mod that is not installed, install the missing dependency.",
+        ),
+        rule(
+            "Incompatible mods",
+            &["Incompatible mods found", "is incompatible with"],
+            "Two installed mods are known to conflict, remove one of them.",
+        ),
+        rule(
+            "Duplicate mods",
+            &[
+                "Duplicate mods found",
+                "Found duplicate mods",
+                "DuplicateModsFoundException",
+            ],
+            "The same mod is installed more than once, remove the older copy.",
+        ),
+        rule(
+            "Mod for a different loader",
+            &[
+                "Mod file is for a different loader",
+                "is a Fabric mod and cannot be loaded",
+                "is a Forge mod",
+            ],
+            "A mod was built for a different mod loader than this instance uses.",
+        ),
+        rule(
+            "Graphics driver problem",
+            &[
+                "Pixel format not accelerated",
+                "GLFW error 65542",
+                "Couldn't set pixel format",
+                "EXCEPTION_ACCESS_VIOLATION",
+            ],
+            "The graphics driver failed, update your graphics driver.",
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRASH_REPORT: &str = "---- Minecraft Crash Report ----
+// Who set us up the TNT?
+
+Time: 2024-01-01 12:00:00
+Description: Ticking entity
+
+java.lang.NullPointerException: Cannot invoke \"Object.toString()\" because \"value\" is null
+\tat com.example.coolmod.entity.CoolEntity.tick(CoolEntity.java:42)
+\tat com.example.coolmod.entity.CoolEntity.baseTick(CoolEntity.java:30)
+\tat net.minecraft.world.entity.Entity.tick(Entity.java:400)
+\tat org.other.helper.Helper.run(Helper.java:7)
+Caused by: java.lang.IllegalStateException: broken state
+\tat com.example.coolmod.State.check(State.java:12)
+\t... 3 more
+
+
+A detailed walkthrough of the error, its code path and all known details is as follows:
+---------------------------------------------------------------------------------------
+
+-- Head --
+Thread: Server thread
+Stacktrace:
+\tat com.example.coolmod.entity.CoolEntity.tick(CoolEntity.java:42)
+";
+
+    const LATEST_LOG: &str = "[12:00:00] [main/INFO]: Loading Minecraft
+[12:00:01] [main/WARN]: Recovered from a problem
+java.io.IOException: first failure
+\tat com.example.first.Loader.load(Loader.java:1)
+[12:00:02] [main/INFO]: Continuing
+[12:00:03] [Render thread/ERROR]: Unreported exception thrown!
+java.lang.RuntimeException: final failure
+\tat com.example.second.Renderer.draw(Renderer.java:5)
+Caused by: java.lang.ClassNotFoundException: com.example.Missing
+\tat java.base/java.net.URLClassLoader.findClass(URLClassLoader.java:445)
+[12:00:04] [Render thread/INFO]: Stopping!
+";
+
+    fn jar(packages: &[&str], configs: &[&str]) -> Jar {
+        Jar {
+            packages: packages.iter().map(|package| package.to_string()).collect(),
+            configs: configs.iter().map(|config| config.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn crash_report_uses_first_chain() {
+        let chain = exception_chains(CRASH_REPORT).into_iter().next().unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].class, "java.lang.NullPointerException");
+        assert_eq!(chain[0].frames.len(), 4);
+        assert_eq!(chain[1].class, "java.lang.IllegalStateException");
+        assert_eq!(chain[1].message, "broken state");
+    }
+
+    #[test]
+    fn log_uses_last_chain() {
+        let chain = exception_chains(LATEST_LOG).pop().unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].class, "java.lang.RuntimeException");
+        assert_eq!(chain[0].message, "final failure");
+        assert_eq!(chain[1].class, "java.lang.ClassNotFoundException");
+    }
+
+    #[test]
+    fn blames_jars_by_frames_and_mixin_configs() {
+        let jars = BTreeMap::from([
+            (
+                String::from("coolmod.jar"),
+                jar(&["com.example.coolmod", "com.example.coolmod.entity"], &[]),
+            ),
+            (String::from("helper.jar"), jar(&["org.other.helper"], &[])),
+            (
+                String::from("mixer.jar"),
+                jar(&["org.mixer"], &["mixer.mixins.json"]),
+            ),
+        ]);
+        let exceptions = exception_chains(CRASH_REPORT).into_iter().next().unwrap();
+        let failures = mixin_failures(
+            "",
+            "[12:00:00] [main/ERROR]: Mixin apply failed mixer.mixins.json:MixinFoo",
+        );
+        let suspects = suspects(&jars, &exceptions, &failures);
+        let names = suspects
+            .iter()
+            .map(|suspect| suspect.jar.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["coolmod.jar", "helper.jar", "mixer.jar"]);
+        assert_eq!(suspects[0].reason, "3 stack frame(s) in the crash");
+    }
+
+    #[test]
+    fn ignores_json_that_is_not_a_mixin_config() {
+        let jars = BTreeMap::from([(String::from("data.jar"), jar(&[], &[]))]);
+        let failures = vec![String::from("Mixin failed to load pack.json")];
+        assert!(suspects(&jars, &[], &failures).is_empty());
+    }
+
+    #[test]
+    fn matches_bundled_rules() {
+        let findings = findings(
+            &bundled_rules(),
+            &["java.lang.OutOfMemoryError: Java heap space"],
+        );
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].title, "Out of memory");
+    }
+}
@@ -0,0 +1,451 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::instance::{Instance, Loader};
+use crate::mods::{Dependency, DependencyKind, Environment, Format, ModInfo};
+use crate::{mods, Errors};
+
+const IGNORED: [&str; 1] = ["java"];
+
+const ALIASES: [(&str, &str); 1] = [("fml", "forge")];
+
+const OPERATORS: [&str; 7] = [">=", "<=", ">", "<", "=", "~", "^"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Problem {
+    pub(crate) severity: Severity,
+    pub(crate) message: String,
+}
+
+struct Provider {
+    name: String,
+    version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Part {
+    Text(String),
+    Number(u64),
+}
+
+pub(crate) fn is_blocking(problems: &[Problem]) -> bool {
+    problems
+        .iter()
+        .any(|problem| problem.severity == Severity::Error)
+}
+
+pub(crate) async fn check(instance: Instance) -> Result<Vec<Problem>, Errors> {
+    let mods = mods::scan(mods::mods_dir(instance.path()))
+        .await?
+        .into_iter()
+        .filter(|info| info.enabled && info.format != Format::Unknown)
+        .collect::<Vec<_>>();
+    let mut problems = vec![];
+    if mods.is_empty() {
+        return Ok(problems);
+    }
+    if instance.loader() == Loader::Vanilla {
+        problems.push(Problem {
+            severity: Severity::Warning,
+            message: String::from("Mods are installed, but vanilla Minecraft does not load them"),
+        });
+        return Ok(problems);
+    }
+
+    let mut files = HashMap::<String, Vec<String>>::new();
+    for info in &mods {
+        files.entry(normalize(&info.id)).or_default().push(
+            info.path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        );
+    }
+    let mut duplicates = files
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .collect::<Vec<_>>();
+    duplicates.sort();
+    for (id, files) in duplicates {
+        problems.push(Problem {
+            severity: Severity::Error,
+            message: format!("{id} is installed more than once: {}", files.join(", ")),
+        });
+    }
+
+    let providers = providers(&instance, &mods);
+    for info in &mods {
+        match compatibility(instance.loader(), info.format) {
+            Some(Severity::Error) => {
+                problems.push(Problem {
+                    severity: Severity::Error,
+                    message: format!(
+                        "{} is a {} mod and cannot be loaded by {}",
+                        info.name,
+                        info.format,
+                        instance.loader()
+                    ),
+                });
+                continue;
+            }
+            Some(Severity::Warning) => problems.push(Problem {
+                severity: Severity::Warning,
+                message: format!(
+                    "{} is a {} mod and may not load on {}",
+                    info.name,
+                    info.format,
+                    instance.loader()
+                ),
+            }),
+            None => {}
+        }
+        for dependency in &info.dependencies {
+            if !IGNORED.contains(&normalize(&dependency.id).as_str())
+                && dependency.side != Environment::Server
+            {
+                problems.extend(check_dependency(info, dependency, &providers));
+            }
+        }
+    }
+    problems.sort_by_key(|problem| problem.severity);
+    Ok(problems)
+}
+
+fn providers(instance: &Instance, mods: &[ModInfo]) -> HashMap<String, Provider> {
+    let mut providers = HashMap::new();
+    let mut provide = |id: &str, name: &str, version: Option<&str>| {
+        providers.entry(normalize(id)).or_insert_with(|| Provider {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+        });
+    };
+
+    let minecraft_version = instance.minecraft_version();
+    provide(
+        "minecraft",
+        "Minecraft",
+        parts(minecraft_version)
+            .iter()
+            .all(|part| matches!(part, Part::Number(_)))
+            .then_some(minecraft_version),
+    );
+    let loader_version = instance
+        .loader_version()
+        .filter(|version| !version.is_empty());
+    match instance.loader() {
+        Loader::Vanilla => {}
+        Loader::Fabric => provide("fabricloader", "Fabric Loader", loader_version),
+        Loader::Quilt => {
+            provide("quilt_loader", "Quilt Loader", loader_version);
+            provide("fabricloader", "Fabric Loader", None);
+        }
+        Loader::Forge => provide("forge", "Forge", loader_version),
+        Loader::NeoForge => {
+            provide("neoforge", "NeoForge", loader_version);
+            provide("forge", "Forge", None);
+        }
+    }
+
+    for info in mods
+        .iter()
+        .chain(mods.iter().flat_map(|info| &info.bundled))
+    {
+        let version = Some(info.version.as_str()).filter(|version| !version.is_empty());
+        provide(&info.id, &info.name, version);
+        for id in &info.provides {
+            provide(id, &info.name, version);
+        }
+    }
+    providers
+}
+
+fn compatibility(loader: Loader, format: Format) -> Option<Severity> {
+    match (loader, format) {
+        (Loader::Fabric, Format::Fabric)
+        | (Loader::Quilt, Format::Quilt | Format::Fabric)
+        | (Loader::Forge, Format::Forge | Format::Legacy)
+        | (Loader::NeoForge, Format::NeoForge) => None,
+        (Loader::NeoForge, Format::Forge) => Some(Severity::Warning),
+        _ => Some(Severity::Error),
+    }
+}
+
+fn check_dependency(
+    info: &ModInfo,
+    dependency: &Dependency,
+    providers: &HashMap<String, Provider>,
+) -> Option<Problem> {
+    let wanted = if dependency.versions.is_empty() {
+        dependency.id.clone()
+    } else {
+        format!("{} {}", dependency.id, dependency.versions.join(" || "))
+    };
+    let Some(provider) = providers.get(&normalize(&dependency.id)) else {
+        let severity = match dependency.kind {
+            DependencyKind::Required => Severity::Error,
+            DependencyKind::Recommended => Severity::Warning,
+            _ => return None,
+        };
+        return Some(Problem {
+            severity,
+            message: format!(
+                "{} {} {wanted}, which is not installed",
+                info.name, dependency.kind
+            ),
+        });
+    };
+
+    let installed = match &provider.version {
+        Some(version) => format!("{} {version}", provider.name),
+        None => provider.name.clone(),
+    };
+    let matched = provider.version.as_deref().is_none_or(|version| {
+        dependency.versions.is_empty()
+            || dependency
+                .versions
+                .iter()
+                .any(|range| matches(info.format, version, range))
+    });
+    let severity = match (dependency.kind, matched) {
+        (DependencyKind::Required, false) => Severity::Error,
+        (DependencyKind::Recommended | DependencyKind::Optional, false) => Severity::Warning,
+        (DependencyKind::Breaks, true) => {
+            return Some(Problem {
+                severity: Severity::Error,
+                message: format!("{} is incompatible with {installed}", info.name),
+            })
+        }
+        (DependencyKind::Conflicts, true) => {
+            return Some(Problem {
+                severity: Severity::Warning,
+                message: format!("{} conflicts with {installed}", info.name),
+            })
+        }
+        _ => return None,
+    };
+    Some(Problem {
+        severity,
+        message: format!(
+            "{} {} {wanted}, but {installed} is installed",
+            info.name, dependency.kind
+        ),
+    })
+}
+
+fn normalize(id: &str) -> String {
+    let id = id.to_lowercase();
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == id)
+        .map_or(id, |(_, canonical)| canonical.to_string())
+}
+
+fn matches(format: Format, version: &str, range: &str) -> bool {
+    match format {
+        Format::Forge | Format::NeoForge | Format::Legacy => matches_maven(version, range),
+        _ => matches_predicate(version, range),
+    }
+}
+
+fn matches_maven(version: &str, range: &str) -> bool {
+    let range = range.trim();
+    if !range.starts_with(['[', '(']) {
+        return true;
+    }
+    let mut restrictions = vec![];
+    let mut rest = range;
+    while let Some(start) = rest.find(['[', '(']) {
+        let Some(end) = rest[start..].find([']', ')']).map(|end| start + end) else {
+            break;
+        };
+        restrictions.push(&rest[start..=end]);
+        rest = &rest[end + 1..];
+    }
+    restrictions.into_iter().any(|restriction| {
+        let lower_inclusive = restriction.starts_with('[');
+        let upper_inclusive = restriction.ends_with(']');
+        let inner = &restriction[1..restriction.len() - 1];
+        let Some((lower, upper)) = inner.split_once(',') else {
+            return compare(version, inner) == Ordering::Equal;
+        };
+        let (lower, upper) = (lower.trim(), upper.trim());
+        let above = lower.is_empty()
+            || match compare(version, lower) {
+                Ordering::Greater => true,
+                Ordering::Equal => lower_inclusive,
+                Ordering::Less => false,
+            };
+        let below = upper.is_empty()
+            || match compare(version, upper) {
+                Ordering::Less => true,
+                Ordering::Equal => upper_inclusive,
+                Ordering::Greater => false,
+            };
+        above && below
+    })
+}
+
+fn matches_predicate(version: &str, predicate: &str) -> bool {
+    let mut terms = vec![];
+    let mut pending = String::new();
+    for term in predicate.split_whitespace() {
+        if OPERATORS.contains(&term) {
+            pending = term.to_string();
+        } else {
+            terms.push(format!("{pending}{term}"));
+            pending.clear();
+        }
+    }
+    terms.iter().all(|term| matches_term(version, term))
+}
+
+fn matches_term(version: &str, term: &str) -> bool {
+    if term == "*" {
+        return true;
+    }
+    let (operator, target) = OPERATORS
+        .iter()
+        .find_map(|operator| Some((*operator, term.strip_prefix(operator)?)))
+        .unwrap_or(("", term));
+    let components = target.split('.').collect::<Vec<_>>();
+    if let Some(wildcard) = components
+        .iter()
+        .position(|component| matches!(*component, "x" | "X" | "*"))
+    {
+        if wildcard == 0 {
+            return true;
+        }
+        let prefix = components[..wildcard].join(".");
+        return compare(version, &prefix) != Ordering::Less
+            && compare(version, &bump(&prefix, wildcard - 1)) == Ordering::Less;
+    }
+    let ordering = compare(version, target);
+    match operator {
+        ">=" => ordering != Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        "~" => {
+            ordering != Ordering::Less
+                && compare(version, &bump(target, usize::from(components.len() > 1)))
+                    == Ordering::Less
+        }
+        "^" => ordering != Ordering::Less && compare(version, &bump(target, 0)) == Ordering::Less,
+        _ => ordering == Ordering::Equal,
+    }
+}
+
+fn bump(version: &str, index: usize) -> String {
+    let (release, _) = split(version);
+    (0..=index)
+        .map(|i| match release.get(i) {
+            Some(Part::Number(number)) if i == index => number + 1,
+            Some(Part::Number(number)) => *number,
+            _ if i == index => 1,
+            _ => 0,
+        })
+        .map(|number| number.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn compare(left: &str, right: &str) -> Ordering {
+    let (left_release, left_pre) = split(left);
+    let (right_release, right_pre) = split(right);
+    let zero = Part::Number(0);
+    for i in 0..left_release.len().max(right_release.len()) {
+        let ordering = left_release
+            .get(i)
+            .unwrap_or(&zero)
+            .cmp(right_release.get(i).unwrap_or(&zero));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    match (left_pre.is_empty(), right_pre.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => left_pre.cmp(&right_pre),
+    }
+}
+
+fn split(version: &str) -> (Vec<Part>, Vec<Part>) {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    let version = version.split('+').next().unwrap_or_default();
+    let (release, pre) = version.split_once('-').unwrap_or((version, ""));
+    (parts(release), parts(pre))
+}
+
+fn parts(version: &str) -> Vec<Part> {
+    version
+        .split(['.', '-', '_'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse()
+                .map(Part::Number)
+                .unwrap_or_else(|_| Part::Text(part.to_lowercase()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maven_ranges() {
+        assert!(matches_maven("47.2.0", "[47,)"));
+        assert!(matches_maven("47.0.0", "[47,48)"));
+        assert!(!matches_maven("48.0.0", "[47,48)"));
+        assert!(!matches_maven("1.20", "(1.20,1.21]"));
+        assert!(matches_maven("1.21", "(1.20,1.21]"));
+        assert!(matches_maven("1.20.1", "[1.20.1]"));
+        assert!(!matches_maven("1.20.2", "[1.20.1]"));
+        assert!(matches_maven("1.12", "(,1.0],[1.2,)"));
+        assert!(!matches_maven("1.1", "(,1.0],[1.2,)"));
+        assert!(matches_maven("anything", "*"));
+    }
+
+    #[test]
+    fn fabric_predicates() {
+        assert!(matches_predicate("0.15.3", ">=0.14.0"));
+        assert!(!matches_predicate("0.13.9", ">=0.14.0"));
+        assert!(matches_predicate("1.20.1", "1.20.x"));
+        assert!(!matches_predicate("1.21", "1.20.x"));
+        assert!(matches_predicate("1.20.4", "~1.20.1"));
+        assert!(!matches_predicate("1.21.0", "~1.20.1"));
+        assert!(matches_predicate("2.9.0", "^2.1"));
+        assert!(!matches_predicate("3.0.0", "^2.1"));
+        assert!(matches_predicate("1.20.1", ">=1.20 <1.21"));
+        assert!(matches_predicate("1.20.1", ">= 1.20 < 1.21"));
+        assert!(!matches_predicate("1.21", ">=1.20 <1.21"));
+        assert!(matches_predicate("5.0.0", "*"));
+        assert!(!matches_predicate("1.0.0-beta.1", ">=1.0.0"));
+        assert!(matches_predicate("1.0.0+build.5", "1.0.0"));
+    }
+
+    #[test]
+    fn normalizes_ids() {
+        assert_eq!(normalize("Forge"), "forge");
+        assert_eq!(normalize("FML"), "forge");
+        assert_eq!(normalize("JEI"), "jei");
+    }
+}
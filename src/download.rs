@@ -0,0 +1,601 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use iced::futures::future::{BoxFuture, Shared};
+use iced::futures::{stream, FutureExt, SinkExt, StreamExt, TryStreamExt};
+use iced::{subscription, Subscription};
+use once_cell::sync::Lazy;
+use reqwest::header::{ETAG, IF_RANGE, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
+
+use crate::api::CLIENT;
+use crate::{hash, store, Errors, CONFIG};
+
+pub(crate) const DEFAULT_CONCURRENCY: usize = 8;
+
+const ATTEMPTS: u32 = 4;
+
+const BACKOFF: Duration = Duration::from_secs(1);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+static RELEASED: Lazy<Notify> = Lazy::new(Notify::new);
+
+static DOWNLOADS: Lazy<Mutex<Table>> = Lazy::new(|| Mutex::new(Table::default()));
+
+static IN_FLIGHT: Lazy<Mutex<HashMap<PathBuf, (Request, Pending)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+type Pending = Shared<BoxFuture<'static, Result<(), Errors>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Hash {
+    Sha1(String),
+    Sha512(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Request {
+    url: String,
+    path: PathBuf,
+    hashes: Vec<Hash>,
+    size: Option<u64>,
+    stored: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum State {
+    Queued,
+    Running,
+    Retrying(u32),
+    Failed(Errors),
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Progress {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) downloaded: u64,
+    pub(crate) total: Option<u64>,
+    pub(crate) state: State,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Snapshot {
+    pub(crate) downloads: Vec<Progress>,
+    pub(crate) finished: usize,
+    pub(crate) downloaded: u64,
+    pub(crate) total: u64,
+}
+
+#[derive(Default)]
+struct Table {
+    next_id: u64,
+    generation: u64,
+    entries: BTreeMap<u64, Entry>,
+    finished: usize,
+    finished_bytes: u64,
+}
+
+struct Entry {
+    request: Request,
+    downloaded: u64,
+    total: Option<u64>,
+    state: State,
+    cancel: Arc<AtomicBool>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Partial {
+    url: String,
+    etag: Option<String>,
+    length: Option<u64>,
+}
+
+struct Guard(u64);
+
+struct Slot;
+
+impl Hash {
+    fn matches(&self, content: &[u8]) -> bool {
+        match self {
+            Hash::Sha1(expected) => hash::sha1(content).eq_ignore_ascii_case(expected),
+            Hash::Sha512(expected) => hash::sha512(content).eq_ignore_ascii_case(expected),
+        }
+    }
+}
+
+impl Request {
+    pub(crate) fn new(url: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Request {
+            url: url.into(),
+            path: path.into(),
+            hashes: vec![],
+            size: None,
+            stored: false,
+        }
+    }
+
+    pub(crate) fn hash(mut self, hash: Option<Hash>) -> Self {
+        self.hashes.extend(hash);
+        self
+    }
+
+    pub(crate) fn size(mut self, size: Option<u64>) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub(crate) fn stored(mut self) -> Self {
+        self.stored = true;
+        self
+    }
+
+    fn sha1(&self) -> Option<&str> {
+        self.hashes.iter().find_map(|hash| match hash {
+            Hash::Sha1(sha1) => Some(sha1.as_str()),
+            Hash::Sha512(_) => None,
+        })
+    }
+
+    fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.url.clone())
+    }
+}
+
+impl State {
+    pub(crate) fn is_active(&self) -> bool {
+        matches!(self, State::Queued | State::Running | State::Retrying(_))
+    }
+}
+
+impl Snapshot {
+    pub(crate) fn is_active(&self) -> bool {
+        self.downloads
+            .iter()
+            .any(|progress| progress.state.is_active())
+    }
+}
+
+impl Table {
+    fn touch(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let active = self
+            .entries
+            .values()
+            .filter(|entry| entry.state.is_active());
+        Snapshot {
+            downloads: self
+                .entries
+                .iter()
+                .map(|(id, entry)| Progress {
+                    id: *id,
+                    name: entry.request.name(),
+                    downloaded: entry.downloaded,
+                    total: entry.total,
+                    state: entry.state.clone(),
+                })
+                .collect(),
+            finished: self.finished,
+            downloaded: self.finished_bytes
+                + active.clone().map(|entry| entry.downloaded).sum::<u64>(),
+            total: self.finished_bytes
+                + active
+                    .map(|entry| entry.total.unwrap_or(entry.downloaded))
+                    .sum::<u64>(),
+        }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let mut table = table();
+        if table
+            .entries
+            .get(&self.0)
+            .is_some_and(|entry| entry.state.is_active())
+        {
+            table.entries.remove(&self.0);
+            table.touch();
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+        RELEASED.notify_waiters();
+    }
+}
+
+pub(crate) async fn fetch(request: Request) -> Result<(), Errors> {
+    loop {
+        let (joined, pending) = {
+            let mut pending_requests = in_flight();
+            match pending_requests.get(&request.path) {
+                Some((running, pending)) => (*running == request, pending.clone()),
+                None => {
+                    let path = request.path.clone();
+                    let pending = {
+                        let request = request.clone();
+                        async move {
+                            let result = enqueue(request).await;
+                            in_flight().remove(&path);
+                            result
+                        }
+                        .boxed()
+                        .shared()
+                    };
+                    pending_requests
+                        .insert(request.path.clone(), (request.clone(), pending.clone()));
+                    (true, pending)
+                }
+            }
+        };
+        let result = pending.await;
+        if joined {
+            return result;
+        }
+    }
+}
+
+async fn enqueue(request: Request) -> Result<(), Errors> {
+    if let Some(sha1) = request.sha1().filter(|_| request.stored) {
+        if store::link(sha1, &request.path).await? {
+            return Ok(());
+        }
+    }
+    let id = {
+        let mut table = table();
+        if !table.entries.values().any(|entry| entry.state.is_active()) {
+            table.finished = 0;
+            table.finished_bytes = 0;
+        }
+        let id = table.next_id;
+        table.next_id += 1;
+        table.entries.insert(
+            id,
+            Entry {
+                total: request.size,
+                request,
+                downloaded: 0,
+                state: State::Queued,
+                cancel: Arc::new(AtomicBool::new(false)),
+            },
+        );
+        table.touch();
+        id
+    };
+    run(id).await
+}
+
+pub(crate) async fn fetch_all(requests: Vec<Request>) -> Result<(), Errors> {
+    stream::iter(requests)
+        .map(fetch)
+        .buffer_unordered(concurrency().await)
+        .try_collect()
+        .await
+}
+
+pub(crate) async fn retry(id: u64) -> Result<(), Errors> {
+    let request = {
+        let mut table = table();
+        let Some(entry) = table
+            .entries
+            .remove(&id)
+            .filter(|entry| !entry.state.is_active())
+        else {
+            return Ok(());
+        };
+        table.touch();
+        entry.request
+    };
+    fetch(request).await
+}
+
+pub(crate) fn cancel(id: u64) {
+    let table = table();
+    if let Some(entry) = table.entries.get(&id) {
+        entry.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn dismiss(id: u64) {
+    let mut table = table();
+    if table
+        .entries
+        .get(&id)
+        .is_some_and(|entry| !entry.state.is_active())
+    {
+        table.entries.remove(&id);
+        table.touch();
+    }
+}
+
+pub(crate) fn progress() -> Subscription<Snapshot> {
+    struct Downloads;
+
+    subscription::channel(
+        std::any::TypeId::of::<Downloads>(),
+        100,
+        |mut output| async move {
+            let mut generation = None;
+            loop {
+                let snapshot = {
+                    let table = table();
+                    (generation != Some(table.generation)).then(|| {
+                        generation = Some(table.generation);
+                        table.snapshot()
+                    })
+                };
+                if let Some(snapshot) = snapshot {
+                    let _ = output.send(snapshot).await;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        },
+    )
+}
+
+async fn run(id: u64) -> Result<(), Errors> {
+    let _guard = Guard(id);
+    let _slot = acquire().await;
+    let Some((request, cancel)) = start(id) else {
+        return Err(Errors::Cancelled);
+    };
+
+    let mut attempt = 1;
+    let result = loop {
+        match transfer(id, &request, &cancel).await {
+            Err(Errors::Cancelled) => break Err(Errors::Cancelled),
+            Err(_) if attempt < ATTEMPTS => {
+                update(id, |entry| entry.state = State::Retrying(attempt));
+                tokio::time::sleep(BACKOFF * 2u32.pow(attempt - 1)).await;
+                if cancel.load(Ordering::Relaxed) {
+                    break Err(Errors::Cancelled);
+                }
+                update(id, |entry| entry.state = State::Running);
+                attempt += 1;
+            }
+            result => break result,
+        }
+    };
+
+    let mut table = table();
+    match &result {
+        Ok(()) => {
+            if let Some(entry) = table.entries.remove(&id) {
+                table.finished += 1;
+                table.finished_bytes += entry.downloaded;
+            }
+        }
+        Err(error) => {
+            if let Some(entry) = table.entries.get_mut(&id) {
+                entry.state = match error {
+                    Errors::Cancelled => State::Cancelled,
+                    error => State::Failed(error.clone()),
+                };
+            }
+        }
+    }
+    table.touch();
+    result
+}
+
+async fn transfer(id: u64, request: &Request, cancel: &AtomicBool) -> Result<(), Errors> {
+    if let Some(parent) = request.path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+    }
+    let part = sibling(&request.path, ".part");
+    let partial = read_partial(&request.path)
+        .await
+        .filter(|partial| partial.url == request.url)
+        .filter(|partial| partial.etag.is_some() || !request.hashes.is_empty());
+    let offset = match partial {
+        Some(_) => fs::metadata(&part)
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    let mut builder = CLIENT.get(&request.url);
+    if offset > 0 {
+        builder = builder.header(RANGE, format!("bytes={offset}-"));
+        if let Some(etag) = partial.as_ref().and_then(|partial| partial.etag.as_ref()) {
+            builder = builder.header(IF_RANGE, etag);
+        }
+    }
+    let mut response = builder
+        .send()
+        .await
+        .map_err(|error| Errors::Http(error.to_string()))?;
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        discard(&request.path).await;
+    }
+    response = response
+        .error_for_status()
+        .map_err(|error| Errors::Http(error.to_string()))?;
+
+    let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { offset } else { 0 };
+    let total = response
+        .content_length()
+        .map(|length| length + downloaded)
+        .or(request.size);
+    if resumed {
+        if partial.is_some_and(|partial| partial.length.is_some() && partial.length != total) {
+            discard(&request.path).await;
+            return Err(Errors::Http(format!(
+                "{} changed on the server",
+                request.name()
+            )));
+        }
+    } else {
+        write_partial(
+            &request.path,
+            &Partial {
+                url: request.url.clone(),
+                etag: response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(String::from),
+                length: total,
+            },
+        )
+        .await?;
+    }
+    update(id, |entry| {
+        entry.downloaded = downloaded;
+        entry.total = total;
+    });
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|error| Errors::Http(error.to_string()))?
+    {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(Errors::Cancelled);
+        }
+        file.write_all(&chunk)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        downloaded += chunk.len() as u64;
+        update(id, |entry| entry.downloaded = downloaded);
+    }
+    file.flush()
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    drop(file);
+
+    if !request.hashes.is_empty() {
+        let content = fs::read(&part)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        if !request.hashes.iter().all(|hash| hash.matches(&content)) {
+            discard(&request.path).await;
+            return Err(Errors::ChecksumMismatch(request.name()));
+        }
+    }
+    let _ = fs::remove_file(sibling(&request.path, ".part.json")).await;
+    if request.stored {
+        store::add(&part, &request.path).await?;
+        fs::remove_file(&part)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))
+    } else {
+        fs::rename(&part, &request.path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))
+    }
+}
+
+fn start(id: u64) -> Option<(Request, Arc<AtomicBool>)> {
+    let mut table = table();
+    let entry = table.entries.get_mut(&id)?;
+    if entry.cancel.load(Ordering::Relaxed) {
+        return None;
+    }
+    entry.state = State::Running;
+    let started = (entry.request.clone(), entry.cancel.clone());
+    table.touch();
+    Some(started)
+}
+
+fn update(id: u64, change: impl FnOnce(&mut Entry)) {
+    let mut table = table();
+    if let Some(entry) = table.entries.get_mut(&id) {
+        change(entry);
+        table.touch();
+    }
+}
+
+fn table() -> MutexGuard<'static, Table> {
+    DOWNLOADS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn in_flight() -> MutexGuard<'static, HashMap<PathBuf, (Request, Pending)>> {
+    IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+async fn acquire() -> Slot {
+    loop {
+        let released = RELEASED.notified();
+        let limit = concurrency().await;
+        if ACTIVE
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |active| {
+                (active < limit).then_some(active + 1)
+            })
+            .is_ok()
+        {
+            return Slot;
+        }
+        let _ = tokio::time::timeout(POLL_INTERVAL, released).await;
+    }
+}
+
+pub(crate) async fn concurrency() -> usize {
+    CONFIG
+        .lock()
+        .await
+        .download_concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .max(1)
+}
+
+async fn read_partial(path: &Path) -> Option<Partial> {
+    let content = fs::read(sibling(path, ".part.json")).await.ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+async fn write_partial(path: &Path, partial: &Partial) -> Result<(), Errors> {
+    fs::write(
+        sibling(path, ".part.json"),
+        serde_json::to_vec(partial).map_err(|error| Errors::Json(error.to_string()))?,
+    )
+    .await
+    .map_err(|error| Errors::Io(error.kind()))
+}
+
+async fn discard(path: &Path) {
+    let _ = fs::remove_file(sibling(path, ".part")).await;
+    let _ = fs::remove_file(sibling(path, ".part.json")).await;
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(extension);
+    PathBuf::from(sibling)
+}
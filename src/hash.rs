@@ -0,0 +1,14 @@
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
+
+pub(crate) fn sha1(content: &[u8]) -> String {
+    hex(&Sha1::digest(content))
+}
+
+pub(crate) fn sha512(content: &[u8]) -> String {
+    hex(&Sha512::digest(content))
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use iced::futures::channel::mpsc::Sender;
+use iced::futures::{future, SinkExt};
+use iced::subscription;
+use iced::Subscription;
+use tokio::fs;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio_stream::wrappers::LinesStream;
+use tokio_stream::StreamExt;
+
+use crate::api::{loader, mojang};
+use crate::instance::{Instance, Loader};
+use crate::{java, launch, Errors, CONFIG};
+
+#[derive(Debug, Clone)]
+pub(crate) enum Progress {
+    Output(String),
+    Finished(Box<Result<Instance, Errors>>),
+}
+
+pub(crate) async fn install(mut instance: Instance) -> Result<Instance, Errors> {
+    match instance.loader() {
+        Loader::Vanilla => {}
+        Loader::Fabric | Loader::Quilt => install_loader_profile(&mut instance).await?,
+        Loader::Forge | Loader::NeoForge => {}
+    }
+    Ok(instance)
+}
+
+pub(crate) fn needs_installer(instance: &Instance) -> bool {
+    matches!(instance.loader(), Loader::Forge | Loader::NeoForge)
+}
+
+pub(crate) fn installer(instance: Instance) -> Subscription<Progress> {
+    subscription::channel(instance.id(), 100, |mut output| async move {
+        let mut log = vec![];
+        let result = run_installer(instance, &mut output, &mut log).await;
+        let _ = output.send(Progress::Finished(Box::new(result))).await;
+
+        future::pending().await
+    })
+}
+
+async fn install_loader_profile(instance: &mut Instance) -> Result<(), Errors> {
+    let versions_path = CONFIG.lock().await.minecraft_dir()?.join("versions");
+    if is_installed(&versions_path, instance) {
+        return Ok(());
+    }
+
+    let loader_version = instance
+        .loader_version()
+        .ok_or(Errors::MissingLoaderVersion)?;
+    let minecraft_version = resolve_minecraft_version(instance).await?;
+    let profile = loader::profile(instance.loader(), &minecraft_version, loader_version).await?;
+    let version_id = profile["id"]
+        .as_str()
+        .ok_or_else(|| Errors::Json(String::from("Loader profile has no id")))?
+        .to_string();
+
+    let version_path = versions_path.join(&version_id);
+    fs::create_dir_all(&version_path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    fs::write(
+        version_path.join(format!("{version_id}.json")),
+        serde_json::to_string_pretty(&profile).map_err(|error| Errors::Json(error.to_string()))?,
+    )
+    .await
+    .map_err(|error| Errors::Io(error.kind()))?;
+
+    instance.set_version_id(&version_id).await
+}
+
+async fn run_installer(
+    mut instance: Instance,
+    output: &mut Sender<Progress>,
+    log: &mut Vec<String>,
+) -> Result<Instance, Errors> {
+    let minecraft_dir = CONFIG.lock().await.minecraft_dir()?;
+    let versions_path = minecraft_dir.join("versions");
+    if is_installed(&versions_path, &instance) {
+        return Ok(instance);
+    }
+
+    let loader_version = instance
+        .loader_version()
+        .ok_or(Errors::MissingLoaderVersion)?
+        .to_string();
+    let minecraft_version = resolve_minecraft_version(&instance).await?;
+    let _ = output
+        .send(Progress::Output(format!(
+            "Downloading {} {loader_version} installer",
+            instance.loader()
+        )))
+        .await;
+    let installer =
+        loader::installer(instance.loader(), &minecraft_version, &loader_version).await?;
+
+    let java = match instance.java_path() {
+        Some(java) => java.to_path_buf(),
+        None => {
+            let version = launch::resolve_version(&minecraft_dir, &minecraft_version).await?;
+            java::select(
+                &java::scan().await,
+                version
+                    .java_version
+                    .map(|java_version| java_version.major_version),
+            )
+            .map_or_else(|| PathBuf::from("java"), |runtime| runtime.path.clone())
+        }
+    };
+    let before = list_versions(&versions_path);
+    let mut child = Command::new(java)
+        .arg("-jar")
+        .arg(&installer)
+        .arg("--installClient")
+        .arg(&minecraft_dir)
+        .current_dir(installer.parent().unwrap_or(&minecraft_dir))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| Errors::Io(error.kind()))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or(Errors::Installer(String::new()))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or(Errors::Installer(String::new()))?;
+    let mut lines = LinesStream::new(BufReader::new(stdout).lines())
+        .merge(LinesStream::new(BufReader::new(stderr).lines()));
+    while let Some(line) = lines.next().await {
+        if let Ok(line) = line {
+            log.push(line.clone());
+            let _ = output.send(Progress::Output(line)).await;
+        }
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    if !status.success() {
+        return Err(Errors::Installer(log.join("\n")));
+    }
+
+    let derived = instance.last_version_id();
+    let version_id = if version_json(&versions_path, &derived).exists() {
+        derived
+    } else {
+        list_versions(&versions_path)
+            .into_iter()
+            .find(|version| !before.contains(version))
+            .ok_or_else(|| Errors::Installer(log.join("\n")))?
+    };
+    instance.set_version_id(&version_id).await?;
+    Ok(instance)
+}
+
+async fn resolve_minecraft_version(instance: &Instance) -> Result<String, Errors> {
+    mojang::manifest()
+        .await?
+        .find(instance.minecraft_version())
+        .map(|version| version.id.clone())
+        .ok_or_else(|| Errors::UnknownVersion(instance.minecraft_version().to_string()))
+}
+
+fn is_installed(versions_path: &Path, instance: &Instance) -> bool {
+    instance
+        .version_id()
+        .is_some_and(|version_id| version_json(versions_path, version_id).exists())
+}
+
+fn version_json(versions_path: &Path, version_id: &str) -> PathBuf {
+    versions_path
+        .join(version_id)
+        .join(format!("{version_id}.json"))
+}
+
+fn list_versions(versions_path: &Path) -> HashSet<String> {
+    std::fs::read_dir(versions_path)
+        .map(|read_dir| {
+            read_dir
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
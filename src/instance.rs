@@ -1,27 +1,124 @@
-use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
 
 use iced::futures::{StreamExt, TryFutureExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use tokio::fs;
 use tokio::fs::read_dir;
 use tokio_stream::wrappers::ReadDirStream;
+use uuid::Uuid;
 
+use crate::process::Run;
 use crate::{paths, Errors};
 
+#[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Instance {
+    #[serde(default)]
+    id: Uuid,
     name: String,
     folder: String,
+    icon: Option<String>,
+    #[serde(default = "latest_release", alias = "version")]
+    minecraft_version: String,
+    #[serde(default)]
+    loader: Loader,
+    loader_version: Option<String>,
+    version_id: Option<String>,
+    java_path: Option<PathBuf>,
+    account: Option<Uuid>,
+    last_run: Option<Run>,
+    #[serde(default)]
+    play_time: Duration,
+    #[serde(default)]
+    mods: BTreeMap<String, ModSource>,
     #[serde(skip)]
     path: PathBuf,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Loader {
+    #[default]
+    Vanilla,
+    Fabric,
+    Quilt,
+    Forge,
+    NeoForge,
+}
+
+impl Loader {
+    pub(crate) const ALL: [Loader; 5] = [
+        Loader::Vanilla,
+        Loader::Fabric,
+        Loader::Quilt,
+        Loader::Forge,
+        Loader::NeoForge,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Platform {
+    Modrinth,
+    CurseForge,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ModSource {
+    pub(crate) platform: Platform,
+    pub(crate) project_id: String,
+    pub(crate) version_id: String,
+    #[serde(default)]
+    pub(crate) pinned: bool,
+}
+
+impl Display for Platform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Platform::Modrinth => "Modrinth",
+            Platform::CurseForge => "CurseForge",
+        })
+    }
+}
+
+impl Display for Loader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Loader::Vanilla => "Vanilla",
+            Loader::Fabric => "Fabric",
+            Loader::Quilt => "Quilt",
+            Loader::Forge => "Forge",
+            Loader::NeoForge => "NeoForge",
+        })
+    }
+}
+
 impl Instance {
-    pub(crate) async fn new(name: &str) -> Result<Self, Errors> {
+    pub(crate) async fn new(
+        name: &str,
+        minecraft_version: &str,
+        loader: Loader,
+        loader_version: Option<&str>,
+    ) -> Result<Self, Errors> {
         let (folder_name, path) = create_folder(name).await?;
         let instance = Self {
+            id: Uuid::new_v4(),
             name: name.to_string(),
             folder: folder_name,
+            icon: None,
+            minecraft_version: minecraft_version.to_string(),
+            loader,
+            loader_version: loader_version
+                .filter(|_| loader != Loader::Vanilla)
+                .map(str::to_string),
+            version_id: None,
+            java_path: None,
+            account: None,
+            last_run: None,
+            play_time: Duration::ZERO,
+            mods: BTreeMap::new(),
             path,
         };
 
@@ -51,13 +148,137 @@ impl Instance {
             .parent()
             .ok_or(Errors::NoParent)?
             .to_path_buf();
+        if instance.id.is_nil() {
+            instance.id = Uuid::new_v4();
+            instance.save().await?;
+        }
         Ok(instance)
     }
 
+    pub(crate) async fn rename(&mut self, name: &str) -> Result<(), Errors> {
+        self.name = name.to_string();
+        self.save().await
+    }
+
+    pub(crate) async fn delete(self) -> Result<(), Errors> {
+        fs::remove_dir_all(&self.path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))
+    }
+
+    pub(crate) fn id(&self) -> Uuid {
+        self.id
+    }
+
     pub(crate) fn name(&self) -> &str {
         &self.name
     }
 
+    pub(crate) fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    pub(crate) async fn set_icon(&mut self, icon: Option<String>) -> Result<(), Errors> {
+        self.icon = icon;
+        self.save().await
+    }
+
+    pub(crate) fn minecraft_version(&self) -> &str {
+        &self.minecraft_version
+    }
+
+    pub(crate) fn loader(&self) -> Loader {
+        self.loader
+    }
+
+    pub(crate) fn loader_version(&self) -> Option<&str> {
+        self.loader_version.as_deref()
+    }
+
+    pub(crate) fn version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+
+    pub(crate) async fn set_version_id(&mut self, version_id: &str) -> Result<(), Errors> {
+        self.version_id = Some(version_id.to_string());
+        self.save().await
+    }
+
+    pub(crate) fn java_path(&self) -> Option<&Path> {
+        self.java_path.as_deref()
+    }
+
+    pub(crate) async fn set_java_path(&mut self, java_path: Option<PathBuf>) -> Result<(), Errors> {
+        self.java_path = java_path;
+        self.save().await
+    }
+
+    pub(crate) fn account(&self) -> Option<Uuid> {
+        self.account
+    }
+
+    pub(crate) async fn set_account(&mut self, account: Option<Uuid>) -> Result<(), Errors> {
+        self.account = account;
+        self.save().await
+    }
+
+    pub(crate) fn last_run(&self) -> Option<&Run> {
+        self.last_run.as_ref()
+    }
+
+    pub(crate) fn play_time(&self) -> Duration {
+        self.play_time
+    }
+
+    pub(crate) async fn record_run(&mut self, run: Run) -> Result<(), Errors> {
+        self.play_time += run.duration;
+        self.last_run = Some(run);
+        self.save().await
+    }
+
+    pub(crate) fn mod_sources(&self) -> &BTreeMap<String, ModSource> {
+        &self.mods
+    }
+
+    pub(crate) async fn set_mod_source(
+        &mut self,
+        file_name: &str,
+        source: Option<ModSource>,
+    ) -> Result<(), Errors> {
+        match source {
+            Some(source) => self.mods.insert(file_name.to_string(), source),
+            None => self.mods.remove(file_name),
+        };
+        self.save().await
+    }
+
+    pub(crate) async fn set_pinned(&mut self, jar_name: &str, pinned: bool) -> Result<(), Errors> {
+        if let Some(source) = self.mods.get_mut(jar_name) {
+            source.pinned = pinned;
+        }
+        self.save().await
+    }
+
+    pub(crate) fn last_version_id(&self) -> String {
+        if let Some(version_id) = &self.version_id {
+            return version_id.clone();
+        }
+        let minecraft_version = &self.minecraft_version;
+        match (self.loader, &self.loader_version) {
+            (Loader::Fabric, Some(loader_version)) => {
+                format!("fabric-loader-{loader_version}-{minecraft_version}")
+            }
+            (Loader::Quilt, Some(loader_version)) => {
+                format!("quilt-loader-{loader_version}-{minecraft_version}")
+            }
+            (Loader::Forge, Some(loader_version)) => {
+                format!("{minecraft_version}-forge-{loader_version}")
+            }
+            (Loader::NeoForge, Some(loader_version)) => format!("neoforge-{loader_version}"),
+            _ => minecraft_version.clone(),
+        }
+    }
+
     pub(crate) fn path(&self) -> &PathBuf {
         &self.path
     }
@@ -103,9 +324,27 @@ pub(crate) async fn collect_instances() -> Result<Vec<Instance>, Errors> {
     .await
 }
 
+fn latest_release() -> String {
+    String::from("latest-release")
+}
+
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    folder_name(name).is_some_and(|folder_name| folder_name == name.trim())
+}
+
+fn folder_name(name: &str) -> Option<String> {
+    let name = name.trim().replace(['/', '\\'], "_");
+    let mut components = Path::new(&name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(component)), None) => Some(component.to_string_lossy().to_string()),
+        _ => None,
+    }
+}
+
 async fn create_folder(name: &str) -> Result<(String, PathBuf), Errors> {
+    let name = folder_name(name).ok_or_else(|| Errors::InvalidFileName(name.to_string()))?;
     let mut counter = 1;
-    let mut folder_name = name.to_string();
+    let mut folder_name = name.clone();
     while paths::INSTANCES.clone()?.join(&folder_name).exists() {
         folder_name = format!("{} ({})", name, counter);
         counter += 1;
@@ -116,3 +355,27 @@ async fn create_folder(name: &str) -> Result<(String, PathBuf), Errors> {
         .await?;
     Ok((folder_name, path))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folder_name_stays_inside_instances() {
+        assert_eq!(folder_name("../../x").as_deref(), Some(".._.._x"));
+        assert_eq!(folder_name("/etc").as_deref(), Some("_etc"));
+        assert_eq!(folder_name(" Survival ").as_deref(), Some("Survival"));
+        assert_eq!(folder_name(".."), None);
+        assert_eq!(folder_name("."), None);
+        assert_eq!(folder_name("  "), None);
+    }
+
+    #[test]
+    fn traversal_names_are_invalid() {
+        assert!(is_valid_name("Survival"));
+        assert!(!is_valid_name("../foo"));
+        assert!(!is_valid_name("a/b"));
+        assert!(!is_valid_name(".."));
+        assert!(!is_valid_name(""));
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tokio::task;
+
+use crate::CONFIG;
+
+#[cfg(windows)]
+const JAVA_BINARY: &str = "java.exe";
+
+#[cfg(not(windows))]
+const JAVA_BINARY: &str = "java";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Runtime {
+    pub(crate) path: PathBuf,
+    pub(crate) version: String,
+    pub(crate) major: u32,
+    pub(crate) vendor: String,
+    pub(crate) arch: String,
+}
+
+impl Display for Runtime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Java {} ({}, {})", self.version, self.vendor, self.arch)
+    }
+}
+
+pub(crate) async fn scan() -> Vec<Runtime> {
+    let minecraft_dir = CONFIG.lock().await.minecraft_dir().ok();
+    let binaries = task::spawn_blocking(move || binaries(minecraft_dir))
+        .await
+        .unwrap_or_default();
+    let mut runtimes = vec![];
+    for binary in binaries {
+        if let Some(runtime) = inspect(&binary).await {
+            runtimes.push(runtime);
+        }
+    }
+    runtimes.sort_by(|a, b| b.major.cmp(&a.major).then_with(|| a.path.cmp(&b.path)));
+    runtimes
+}
+
+pub(crate) fn select(runtimes: &[Runtime], major: Option<u32>) -> Option<&Runtime> {
+    let native = |runtime: &&Runtime| is_native_arch(&runtime.arch);
+    match major {
+        Some(major) => runtimes
+            .iter()
+            .filter(|runtime| runtime.major == major)
+            .find(native)
+            .or_else(|| runtimes.iter().find(|runtime| runtime.major == major))
+            .or_else(|| {
+                runtimes
+                    .iter()
+                    .filter(|runtime| runtime.major > major)
+                    .min_by_key(|runtime| runtime.major)
+            }),
+        None => runtimes.iter().find(native).or(runtimes.first()),
+    }
+}
+
+pub(crate) async fn inspect(binary: &Path) -> Option<Runtime> {
+    let home = binary.parent()?.parent()?;
+    if let Some(runtime) = read_release(binary, &home.join("release")).await {
+        return Some(runtime);
+    }
+
+    let output = Command::new(binary)
+        .arg("-XshowSettings:properties")
+        .arg("-version")
+        .output()
+        .await
+        .ok()?;
+    let output = String::from_utf8_lossy(&output.stderr);
+    let property = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.trim().split_once(" = ")?;
+            (key == name).then(|| value.trim().to_string())
+        })
+    };
+    let version = property("java.version")?;
+    Some(Runtime {
+        path: binary.to_path_buf(),
+        major: major_version(&version)?,
+        version,
+        vendor: property("java.vendor").unwrap_or_default(),
+        arch: property("os.arch").unwrap_or_default(),
+    })
+}
+
+async fn read_release(binary: &Path, release: &Path) -> Option<Runtime> {
+    let contents = tokio::fs::read_to_string(release).await.ok()?;
+    let property = |name: &str| {
+        contents.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key == name).then(|| value.trim().trim_matches('"').to_string())
+        })
+    };
+    let version = property("JAVA_VERSION")?;
+    Some(Runtime {
+        path: binary.to_path_buf(),
+        major: major_version(&version)?,
+        version,
+        vendor: property("IMPLEMENTOR").unwrap_or_default(),
+        arch: property("OS_ARCH").unwrap_or_default(),
+    })
+}
+
+fn major_version(version: &str) -> Option<u32> {
+    let mut parts = version.split(['.', '_', '-', '+']);
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+fn is_native_arch(arch: &str) -> bool {
+    match env::consts::ARCH {
+        "x86_64" => arch == "x86_64" || arch == "amd64",
+        "aarch64" => arch == "aarch64" || arch == "arm64",
+        "x86" => arch == "x86" || arch == "i386" || arch == "i686",
+        other => arch == other,
+    }
+}
+
+fn binaries(minecraft_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    candidates(minecraft_dir)
+        .into_iter()
+        .filter_map(|home| home.join("bin").join(JAVA_BINARY).canonicalize().ok())
+        .filter(|binary| seen.insert(binary.clone()))
+        .collect()
+}
+
+fn candidates(minecraft_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut candidates = vec![];
+
+    if let Some(java_home) = env::var_os("JAVA_HOME") {
+        candidates.push(PathBuf::from(java_home));
+    }
+    if let Some(path) = env::var_os("PATH") {
+        for directory in env::split_paths(&path) {
+            let binary = directory.join(JAVA_BINARY);
+            if let Some(home) = binary
+                .canonicalize()
+                .ok()
+                .and_then(|binary| binary.parent()?.parent().map(Path::to_path_buf))
+            {
+                candidates.push(home);
+            }
+        }
+    }
+
+    let mut roots = vec![
+        PathBuf::from("/usr/lib/jvm"),
+        PathBuf::from("/usr/lib64/jvm"),
+        PathBuf::from("/usr/java"),
+        PathBuf::from("/opt/java"),
+        PathBuf::from("/opt"),
+        PathBuf::from("/Library/Java/JavaVirtualMachines"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join(".sdkman/candidates/java"));
+        roots.push(home.join(".jdks"));
+        roots.push(home.join(".gradle/jdks"));
+        roots.push(home.join("Library/Java/JavaVirtualMachines"));
+    }
+    for program_files in ["ProgramFiles", "ProgramFiles(x86)"] {
+        if let Some(program_files) = env::var_os(program_files) {
+            let program_files = PathBuf::from(program_files);
+            for vendor in [
+                "Java",
+                "Eclipse Adoptium",
+                "Microsoft",
+                "Zulu",
+                "Amazon Corretto",
+            ] {
+                roots.push(program_files.join(vendor));
+            }
+        }
+    }
+    if let Some(minecraft_dir) = minecraft_dir {
+        for runtime in children(&minecraft_dir.join("runtime")) {
+            for platform in children(&runtime) {
+                roots.push(platform);
+            }
+        }
+    }
+
+    for root in roots {
+        for home in children(&root) {
+            candidates.push(home.join("Contents/Home"));
+            candidates.push(home);
+        }
+    }
+
+    candidates
+}
+
+fn children(path: &Path) -> Vec<PathBuf> {
+    fs::read_dir(path)
+        .map(|read_dir| {
+            read_dir
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
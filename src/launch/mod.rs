@@ -0,0 +1,489 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::process::{Child, Command};
+use uuid::{Builder, Uuid};
+use zip::ZipArchive;
+
+use crate::api::mojang;
+use crate::download::{Hash, Request};
+use crate::instance::Instance;
+use crate::launch::version::{maven_path, Argument, AssetIndex, Library, Version};
+use crate::{download, java, Errors, CONFIG};
+
+pub(crate) mod version;
+
+const LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+
+const RESOURCES_URL: &str = "https://resources.download.minecraft.net";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum LaunchMode {
+    #[default]
+    Launcher,
+    Direct,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Session {
+    name: String,
+    uuid: Uuid,
+    access_token: String,
+    user_type: String,
+    xuid: String,
+}
+
+impl Session {
+    pub(crate) fn offline(name: &str) -> Self {
+        let digest = Md5::digest(format!("OfflinePlayer:{name}").as_bytes());
+        Session {
+            name: name.to_string(),
+            uuid: Builder::from_md5_bytes(digest.into()).into_uuid(),
+            access_token: String::from("0"),
+            user_type: String::from("legacy"),
+            xuid: String::from("0"),
+        }
+    }
+
+    pub(crate) fn new(name: &str, uuid: Uuid, access_token: &str, xuid: &str) -> Self {
+        Session {
+            name: name.to_string(),
+            uuid,
+            access_token: access_token.to_string(),
+            user_type: String::from("msa"),
+            xuid: xuid.to_string(),
+        }
+    }
+
+    pub(crate) fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+}
+
+struct Assets {
+    root: PathBuf,
+    game_assets: PathBuf,
+    index_name: String,
+}
+
+pub(crate) async fn launch(instance: &Instance, session: Session) -> Result<Child, Errors> {
+    let root = CONFIG.lock().await.minecraft_dir()?;
+    let version = resolve_version(&root, &instance.last_version_id()).await?;
+    let natives_path = root.join("versions").join(&version.id).join("natives");
+
+    let mut classpath = download_libraries(&root, &version, &natives_path).await?;
+    classpath.push(download_client(&root, &version).await?);
+    let assets = download_assets(&root, &version, instance.path()).await?;
+
+    let separator = if cfg!(windows) { ";" } else { ":" };
+    let variables = HashMap::from([
+        ("auth_player_name", session.name.clone()),
+        ("version_name", version.id.clone()),
+        ("game_directory", display(instance.path())),
+        ("assets_root", display(&assets.root)),
+        ("game_assets", display(&assets.game_assets)),
+        ("assets_index_name", assets.index_name.clone()),
+        ("auth_uuid", session.uuid.simple().to_string()),
+        ("auth_access_token", session.access_token.clone()),
+        ("auth_session", session.access_token.clone()),
+        ("auth_xuid", session.xuid.clone()),
+        ("clientid", String::new()),
+        ("user_type", session.user_type.clone()),
+        ("user_properties", String::from("{}")),
+        (
+            "version_type",
+            version
+                .version_type
+                .clone()
+                .unwrap_or_else(|| String::from("release")),
+        ),
+        ("natives_directory", display(&natives_path)),
+        ("launcher_name", String::from("aethon")),
+        ("launcher_version", String::from(env!("CARGO_PKG_VERSION"))),
+        ("library_directory", display(&root.join("libraries"))),
+        ("classpath_separator", String::from(separator)),
+        (
+            "classpath",
+            classpath
+                .iter()
+                .map(|path| display(path))
+                .collect::<Vec<_>>()
+                .join(separator),
+        ),
+    ]);
+
+    let arguments = version.arguments.clone().unwrap_or_default();
+    let mut jvm = evaluate(arguments.jvm);
+    if !jvm.iter().any(|argument| argument.contains("${classpath}")) {
+        jvm.extend([
+            String::from("-Djava.library.path=${natives_directory}"),
+            String::from("-cp"),
+            String::from("${classpath}"),
+        ]);
+    }
+    let game = match &version.minecraft_arguments {
+        Some(arguments) => arguments.split_whitespace().map(str::to_string).collect(),
+        None => evaluate(arguments.game),
+    };
+    let main_class = version
+        .main_class
+        .clone()
+        .ok_or_else(|| Errors::Json(format!("Version {} has no main class", version.id)))?;
+
+    let java = match instance.java_path() {
+        Some(java) => java.to_path_buf(),
+        None => java::select(
+            &java::scan().await,
+            version
+                .java_version
+                .map(|java_version| java_version.major_version),
+        )
+        .map_or_else(|| PathBuf::from("java"), |runtime| runtime.path.clone()),
+    };
+
+    fs::create_dir_all(instance.path())
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    Command::new(java)
+        .args(jvm.iter().map(|argument| substitute(argument, &variables)))
+        .arg(main_class)
+        .args(game.iter().map(|argument| substitute(argument, &variables)))
+        .current_dir(instance.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+pub(crate) async fn resolve_version(root: &Path, id: &str) -> Result<Version, Errors> {
+    let mut version = load_version(root, id).await?;
+    while let Some(parent) = version.inherits_from.clone() {
+        version = version.inherit(load_version(root, &parent).await?);
+    }
+    Ok(version)
+}
+
+async fn load_version(root: &Path, id: &str) -> Result<Version, Errors> {
+    let path = root.join("versions").join(id).join(format!("{id}.json"));
+    let contents = if path.exists() {
+        fs::read_to_string(&path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?
+    } else {
+        let manifest = mojang::manifest().await?;
+        let entry = manifest
+            .find(id)
+            .ok_or_else(|| Errors::UnknownVersion(id.to_string()))?;
+        let contents = serde_json::to_string(&mojang::version(entry).await?)
+            .map_err(|error| Errors::Json(error.to_string()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|error| Errors::Io(error.kind()))?;
+        }
+        fs::write(&path, &contents)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        contents
+    };
+    serde_json::from_str(&contents).map_err(|error| Errors::Json(error.to_string()))
+}
+
+async fn download_libraries(
+    root: &Path,
+    version: &Version,
+    natives_path: &Path,
+) -> Result<Vec<PathBuf>, Errors> {
+    let libraries_path = root.join("libraries");
+    let mut seen = HashSet::new();
+    let mut classpath = vec![];
+    let mut natives = vec![];
+
+    for library in version
+        .libraries
+        .iter()
+        .filter(|library| library.is_allowed())
+    {
+        if let Some(classifier) = library.native_classifier() {
+            let artifact = library
+                .downloads
+                .as_ref()
+                .and_then(|downloads| downloads.classifiers.get(&classifier));
+            let path = match artifact.and_then(|artifact| artifact.path.clone()) {
+                Some(path) => path,
+                None => maven_path(&format!("{}:{classifier}", library.name))
+                    .ok_or_else(|| Errors::MissingLibrary(library.name.clone()))?,
+            };
+            let url = artifact.map(|artifact| artifact.url.clone());
+            let size = artifact.and_then(|artifact| artifact.size);
+            let sha1 = artifact.and_then(|artifact| artifact.sha1.clone());
+            let path = libraries_path.join(path);
+            fetch_library(library, &path, url, size, sha1).await?;
+            natives.push((library, path));
+            continue;
+        }
+
+        if library.natives.is_some() || !seen.insert(library_key(&library.name)) {
+            continue;
+        }
+        let artifact = library
+            .downloads
+            .as_ref()
+            .and_then(|downloads| downloads.artifact.as_ref());
+        let relative = match artifact.and_then(|artifact| artifact.path.clone()) {
+            Some(path) => path,
+            None => maven_path(&library.name)
+                .ok_or_else(|| Errors::MissingLibrary(library.name.clone()))?,
+        };
+        let url = match artifact {
+            Some(artifact) => Some(artifact.url.clone()),
+            None => Some(format!(
+                "{}{relative}",
+                library.url.as_deref().unwrap_or(LIBRARIES_URL)
+            )),
+        };
+        let path = libraries_path.join(relative);
+        fetch_library(
+            library,
+            &path,
+            url,
+            artifact.and_then(|artifact| artifact.size),
+            artifact.and_then(|artifact| artifact.sha1.clone()),
+        )
+        .await?;
+        classpath.push(path);
+    }
+
+    extract_natives(&natives, natives_path)?;
+    Ok(classpath)
+}
+
+async fn fetch_library(
+    library: &Library,
+    path: &Path,
+    url: Option<String>,
+    size: Option<u64>,
+    sha1: Option<String>,
+) -> Result<(), Errors> {
+    if is_present(path, size) {
+        return Ok(());
+    }
+    match url.filter(|url| !url.is_empty()) {
+        Some(url) => {
+            download::fetch(
+                Request::new(url, path)
+                    .hash(sha1.map(Hash::Sha1))
+                    .size(size)
+                    .stored(),
+            )
+            .await
+        }
+        None => Err(Errors::MissingLibrary(library.name.clone())),
+    }
+}
+
+fn extract_natives(natives: &[(&Library, PathBuf)], natives_path: &Path) -> Result<(), Errors> {
+    if natives_path.exists() {
+        std::fs::remove_dir_all(natives_path).map_err(|error| Errors::Io(error.kind()))?;
+    }
+    std::fs::create_dir_all(natives_path).map_err(|error| Errors::Io(error.kind()))?;
+
+    for (library, path) in natives {
+        let mut archive =
+            ZipArchive::new(File::open(path).map_err(|error| Errors::Io(error.kind()))?)
+                .map_err(|error| Errors::Archive(error.to_string()))?;
+        let exclude = library
+            .extract
+            .as_ref()
+            .map(|extract| extract.exclude.clone())
+            .unwrap_or_default();
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|error| Errors::Archive(error.to_string()))?;
+            let Some(name) = file.enclosed_name().map(Path::to_path_buf) else {
+                continue;
+            };
+            let name_text = name.to_string_lossy().replace('\\', "/");
+            if file.is_dir()
+                || name_text.starts_with("META-INF/")
+                || exclude.iter().any(|exclude| name_text.starts_with(exclude))
+            {
+                continue;
+            }
+            let target = natives_path.join(name);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|error| Errors::Io(error.kind()))?;
+            }
+            io::copy(
+                &mut file,
+                &mut File::create(target).map_err(|error| Errors::Io(error.kind()))?,
+            )
+            .map_err(|error| Errors::Io(error.kind()))?;
+        }
+    }
+    Ok(())
+}
+
+async fn download_client(root: &Path, version: &Version) -> Result<PathBuf, Errors> {
+    let jar = version.jar();
+    let path = root.join("versions").join(jar).join(format!("{jar}.jar"));
+    let client = version
+        .downloads
+        .as_ref()
+        .and_then(|downloads| downloads.client.as_ref());
+    if is_present(&path, client.and_then(|client| client.size)) {
+        return Ok(path);
+    }
+    match client {
+        Some(client) => {
+            download::fetch(
+                Request::new(&client.url, &path)
+                    .hash(client.sha1.clone().map(Hash::Sha1))
+                    .size(client.size),
+            )
+            .await?
+        }
+        None => return Err(Errors::MissingLibrary(format!("{jar}.jar"))),
+    }
+    Ok(path)
+}
+
+async fn download_assets(
+    root: &Path,
+    version: &Version,
+    game_path: &Path,
+) -> Result<Assets, Errors> {
+    let assets_path = root.join("assets");
+    let Some(info) = &version.asset_index else {
+        return Ok(Assets {
+            root: assets_path.clone(),
+            game_assets: assets_path,
+            index_name: version.assets.clone().unwrap_or_default(),
+        });
+    };
+
+    let index_path = assets_path
+        .join("indexes")
+        .join(format!("{}.json", info.id));
+    if !index_path.exists() {
+        download::fetch(
+            Request::new(&info.url, &index_path).hash(info.sha1.clone().map(Hash::Sha1)),
+        )
+        .await?;
+    }
+    let index = serde_json::from_str::<AssetIndex>(
+        &fs::read_to_string(&index_path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?,
+    )
+    .map_err(|error| Errors::Json(error.to_string()))?;
+
+    let objects_path = assets_path.join("objects");
+    let mut missing = vec![];
+    for object in index.objects.values() {
+        let relative = object.relative_path()?;
+        let path = objects_path.join(&relative);
+        if !is_present(&path, Some(object.size)) {
+            missing.push(
+                Request::new(format!("{RESOURCES_URL}/{relative}"), path)
+                    .hash(Some(Hash::Sha1(object.hash.clone())))
+                    .size(Some(object.size)),
+            );
+        }
+    }
+    download::fetch_all(missing).await?;
+
+    let game_assets = if index.map_to_resources {
+        game_path.join("resources")
+    } else if index.is_virtual {
+        assets_path.join("virtual").join(&info.id)
+    } else {
+        assets_path.clone()
+    };
+    if index.map_to_resources || index.is_virtual {
+        for (name, object) in &index.objects {
+            let target = game_assets.join(name);
+            if is_present(&target, Some(object.size)) {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .await
+                    .map_err(|error| Errors::Io(error.kind()))?;
+            }
+            fs::copy(objects_path.join(object.relative_path()?), target)
+                .await
+                .map_err(|error| Errors::Io(error.kind()))?;
+        }
+    }
+
+    Ok(Assets {
+        root: assets_path,
+        game_assets,
+        index_name: info.id.clone(),
+    })
+}
+
+fn evaluate(arguments: Vec<Argument>) -> Vec<String> {
+    arguments
+        .into_iter()
+        .flat_map(|argument| match argument {
+            Argument::Plain(value) => vec![value],
+            Argument::Conditional { rules, value } => {
+                if version::is_allowed(&rules) {
+                    value.into_vec()
+                } else {
+                    vec![]
+                }
+            }
+        })
+        .collect()
+}
+
+fn substitute(argument: &str, variables: &HashMap<&str, String>) -> String {
+    let mut result = String::new();
+    let mut rest = argument;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            result.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let key = &rest[start + 2..start + end];
+        match variables.get(key) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn library_key(name: &str) -> String {
+    let mut parts = name.split('@').next().unwrap_or(name).split(':');
+    let group = parts.next().unwrap_or_default();
+    let artifact = parts.next().unwrap_or_default();
+    let _version = parts.next();
+    match parts.next() {
+        Some(classifier) => format!("{group}:{artifact}:{classifier}"),
+        None => format!("{group}:{artifact}"),
+    }
+}
+
+fn is_present(path: &Path, size: Option<u64>) -> bool {
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && size.is_none_or(|size| metadata.len() == size))
+}
+
+fn display(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
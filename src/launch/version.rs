@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::Errors;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Version {
+    pub(crate) id: String,
+    pub(crate) inherits_from: Option<String>,
+    #[serde(rename = "type")]
+    pub(crate) version_type: Option<String>,
+    pub(crate) main_class: Option<String>,
+    pub(crate) minecraft_arguments: Option<String>,
+    pub(crate) arguments: Option<Arguments>,
+    #[serde(default)]
+    pub(crate) libraries: Vec<Library>,
+    pub(crate) asset_index: Option<AssetIndexInfo>,
+    pub(crate) assets: Option<String>,
+    pub(crate) downloads: Option<Downloads>,
+    pub(crate) java_version: Option<JavaVersion>,
+    pub(crate) jar: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JavaVersion {
+    pub(crate) major_version: u32,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct Arguments {
+    #[serde(default)]
+    pub(crate) game: Vec<Argument>,
+    #[serde(default)]
+    pub(crate) jvm: Vec<Argument>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Argument {
+    Plain(String),
+    Conditional { rules: Vec<Rule>, value: Values },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Values {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Rule {
+    action: Action,
+    os: Option<OsRule>,
+    features: Option<HashMap<String, bool>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Action {
+    Allow,
+    Disallow,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OsRule {
+    name: Option<String>,
+    arch: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Library {
+    pub(crate) name: String,
+    pub(crate) downloads: Option<LibraryDownloads>,
+    pub(crate) url: Option<String>,
+    pub(crate) rules: Option<Vec<Rule>>,
+    pub(crate) natives: Option<HashMap<String, String>>,
+    pub(crate) extract: Option<Extract>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct LibraryDownloads {
+    pub(crate) artifact: Option<Artifact>,
+    #[serde(default)]
+    pub(crate) classifiers: HashMap<String, Artifact>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Extract {
+    #[serde(default)]
+    pub(crate) exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Artifact {
+    pub(crate) path: Option<String>,
+    pub(crate) url: String,
+    pub(crate) size: Option<u64>,
+    pub(crate) sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct AssetIndexInfo {
+    pub(crate) id: String,
+    pub(crate) url: String,
+    pub(crate) sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Downloads {
+    pub(crate) client: Option<Artifact>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct AssetIndex {
+    pub(crate) objects: HashMap<String, AssetObject>,
+    #[serde(default, rename = "virtual")]
+    pub(crate) is_virtual: bool,
+    #[serde(default)]
+    pub(crate) map_to_resources: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct AssetObject {
+    pub(crate) hash: String,
+    pub(crate) size: u64,
+}
+
+impl AssetObject {
+    pub(crate) fn relative_path(&self) -> Result<String, Errors> {
+        match self.hash.get(..2) {
+            Some(prefix) if self.hash.len() == 40 => Ok(format!("{prefix}/{}", self.hash)),
+            _ => Err(Errors::Json(format!("invalid asset hash {}", self.hash))),
+        }
+    }
+}
+
+impl Version {
+    pub(crate) fn inherit(self, parent: Version) -> Version {
+        let arguments = match (parent.arguments, self.arguments) {
+            (Some(mut parent), Some(child)) => {
+                parent.game.extend(child.game);
+                parent.jvm.extend(child.jvm);
+                Some(parent)
+            }
+            (parent, child) => child.or(parent),
+        };
+        let mut libraries = self.libraries;
+        libraries.extend(parent.libraries);
+
+        Version {
+            jar: self.jar.or(parent.jar).or(Some(parent.id)),
+            id: self.id,
+            inherits_from: parent.inherits_from,
+            version_type: self.version_type.or(parent.version_type),
+            main_class: self.main_class.or(parent.main_class),
+            minecraft_arguments: self.minecraft_arguments.or(parent.minecraft_arguments),
+            arguments,
+            libraries,
+            asset_index: self.asset_index.or(parent.asset_index),
+            assets: self.assets.or(parent.assets),
+            downloads: self.downloads.or(parent.downloads),
+            java_version: self.java_version.or(parent.java_version),
+        }
+    }
+
+    pub(crate) fn jar(&self) -> &str {
+        self.jar.as_deref().unwrap_or(&self.id)
+    }
+}
+
+impl Library {
+    pub(crate) fn is_allowed(&self) -> bool {
+        self.rules.as_deref().is_none_or(is_allowed)
+    }
+
+    pub(crate) fn native_classifier(&self) -> Option<String> {
+        self.natives
+            .as_ref()?
+            .get(os_name())
+            .map(|classifier| classifier.replace("${arch}", arch_bits()))
+    }
+}
+
+impl Values {
+    pub(crate) fn into_vec(self) -> Vec<String> {
+        match self {
+            Values::Single(value) => vec![value],
+            Values::Multiple(values) => values,
+        }
+    }
+}
+
+pub(crate) fn is_allowed(rules: &[Rule]) -> bool {
+    if rules.is_empty() {
+        return true;
+    }
+    rules.iter().fold(false, |allowed, rule| {
+        if rule.matches() {
+            rule.action == Action::Allow
+        } else {
+            allowed
+        }
+    })
+}
+
+impl Rule {
+    fn matches(&self) -> bool {
+        let os_matches = self.os.as_ref().is_none_or(|os| {
+            os.name.as_deref().is_none_or(|name| name == os_name())
+                && os.arch.as_deref().is_none_or(|arch| arch == os_arch())
+        });
+        let features_match = self
+            .features
+            .as_ref()
+            .is_none_or(|features| features.values().all(|enabled| !enabled));
+        os_matches && features_match
+    }
+}
+
+pub(crate) fn maven_path(name: &str) -> Option<String> {
+    let (name, extension) = name.split_once('@').unwrap_or((name, "jar"));
+    let mut parts = name.split(':');
+    let group = parts.next()?;
+    let artifact = parts.next()?;
+    let version = parts.next()?;
+    let file_name = match parts.next() {
+        Some(classifier) => format!("{artifact}-{version}-{classifier}.{extension}"),
+        None => format!("{artifact}-{version}.{extension}"),
+    };
+    Some(format!(
+        "{}/{artifact}/{version}/{file_name}",
+        group.replace('.', "/")
+    ))
+}
+
+pub(crate) fn os_name() -> &'static str {
+    match std::env::consts::OS {
+        "windows" => "windows",
+        "macos" => "osx",
+        _ => "linux",
+    }
+}
+
+fn os_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "x86",
+        "aarch64" => "arm64",
+        _ => "x86_64",
+    }
+}
+
+fn arch_bits() -> &'static str {
+    if cfg!(target_pointer_width = "64") {
+        "64"
+    } else {
+        "32"
+    }
+}
@@ -0,0 +1,320 @@
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use iced::futures::SinkExt;
+use iced::{subscription, Subscription};
+use tokio::fs;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use uuid::Uuid;
+
+use crate::{process, Errors};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Level {
+    Fatal,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub(crate) const ALL: [Level; 6] = [
+        Level::Fatal,
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    fn parse(level: &str) -> Option<Level> {
+        match level.trim().to_ascii_uppercase().as_str() {
+            "FATAL" => Some(Level::Fatal),
+            "ERROR" | "SEVERE" => Some(Level::Error),
+            "WARN" | "WARNING" => Some(Level::Warn),
+            "INFO" => Some(Level::Info),
+            "DEBUG" | "FINE" => Some(Level::Debug),
+            "TRACE" | "FINER" | "FINEST" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Level::Fatal => "FATAL",
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    File,
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Line {
+    pub(crate) time: String,
+    pub(crate) thread: String,
+    pub(crate) level: Level,
+    pub(crate) message: String,
+    pub(crate) source: Source,
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.time.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(
+                f,
+                "[{}] [{}/{}]: {}",
+                self.time, self.thread, self.level, self.message
+            )
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Parser {
+    event: Option<String>,
+    last: Option<(Level, String)>,
+}
+
+impl Parser {
+    pub(crate) fn push(&mut self, raw: &str, source: Source) -> Option<Line> {
+        let raw = raw.trim_end_matches(['\r', '\n']);
+        if let Some(event) = &mut self.event {
+            event.push('\n');
+            event.push_str(raw);
+            if !raw.contains("</log4j:Event>") {
+                return None;
+            }
+            let event = self.event.take().unwrap_or_default();
+            return self.finish(parse_event(&event, source));
+        }
+        if raw.trim_start().starts_with("<log4j:Event") {
+            if raw.contains("</log4j:Event>") {
+                return self.finish(parse_event(raw, source));
+            }
+            self.event = Some(raw.to_string());
+            return None;
+        }
+        if let Some(line) = parse_plain(raw, source) {
+            return self.finish(Some(line));
+        }
+
+        let (level, thread) = self
+            .last
+            .clone()
+            .unwrap_or_else(|| (default_level(source), String::new()));
+        Some(Line {
+            time: String::new(),
+            thread,
+            level,
+            message: raw.to_string(),
+            source,
+        })
+    }
+
+    fn finish(&mut self, line: Option<Line>) -> Option<Line> {
+        if let Some(line) = &line {
+            self.last = Some((line.level, line.thread.clone()));
+        }
+        line
+    }
+}
+
+pub(crate) fn latest(instance_path: &Path) -> PathBuf {
+    instance_path.join("logs").join("latest.log")
+}
+
+pub(crate) fn archives(instance_path: &Path) -> Vec<PathBuf> {
+    let mut archives = std::fs::read_dir(instance_path.join("logs"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.to_string_lossy().ends_with(".log.gz"))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    archives.sort_by(|a, b| b.cmp(a));
+    archives
+}
+
+pub(crate) async fn read(path: PathBuf) -> Result<Vec<Line>, Errors> {
+    let data = fs::read(&path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    let content = if path.extension().is_some_and(|extension| extension == "gz") {
+        let mut content = String::new();
+        GzDecoder::new(data.as_slice())
+            .read_to_string(&mut content)
+            .map_err(|error| Errors::Archive(error.to_string()))?;
+        content
+    } else {
+        String::from_utf8_lossy(&data).to_string()
+    };
+
+    let mut parser = Parser::default();
+    Ok(content
+        .lines()
+        .filter_map(|line| parser.push(line, Source::File))
+        .collect())
+}
+
+pub(crate) fn tail(id: Uuid, path: PathBuf) -> Subscription<Vec<Line>> {
+    subscription::channel(id, 100, move |mut output| async move {
+        let mut parser = Parser::default();
+        let captured = process::is_captured(id).await;
+        let mut offset = 0;
+        let mut partial = String::new();
+        let mut cursor = 0;
+        loop {
+            let mut lines = vec![];
+
+            if captured {
+                let (next, captured) = process::output(id, cursor).await;
+                cursor = next;
+                lines.extend(
+                    captured
+                        .into_iter()
+                        .filter_map(|(source, line)| parser.push(&line, source)),
+                );
+            } else if let Ok(content) = read_from(&path, &mut offset).await {
+                partial.push_str(&content);
+                while let Some(end) = partial.find('\n') {
+                    let line = partial.drain(..=end).collect::<String>();
+                    lines.extend(parser.push(&line, Source::File));
+                }
+            }
+
+            if !lines.is_empty() {
+                let _ = output.send(lines).await;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+}
+
+async fn read_from(path: &Path, offset: &mut u64) -> Result<String, Errors> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    let length = file
+        .metadata()
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?
+        .len();
+    if length < *offset {
+        *offset = 0;
+    }
+    file.seek(std::io::SeekFrom::Start(*offset))
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    let mut content = vec![];
+    file.read_to_end(&mut content)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    *offset += content.len() as u64;
+    Ok(String::from_utf8_lossy(&content).to_string())
+}
+
+fn parse_plain(raw: &str, source: Source) -> Option<Line> {
+    let rest = raw.strip_prefix('[')?;
+    let (time, rest) = rest.split_once("] [")?;
+    let (thread_level, rest) = rest.split_once(']')?;
+    let (thread, level) = thread_level.rsplit_once('/')?;
+    let level = Level::parse(level)?;
+    let message = rest.trim_start();
+    let message = message.strip_prefix(':').unwrap_or(message).trim_start();
+    Some(Line {
+        time: time.to_string(),
+        thread: thread.to_string(),
+        level,
+        message: message.to_string(),
+        source,
+    })
+}
+
+fn parse_event(event: &str, source: Source) -> Option<Line> {
+    let level = Level::parse(&attribute(event, "level")?)?;
+    let time = attribute(event, "timestamp")
+        .and_then(|timestamp| timestamp.parse::<u64>().ok())
+        .map(|timestamp| {
+            let seconds = timestamp / 1000 % 86400;
+            format!(
+                "{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        })
+        .unwrap_or_default();
+    let mut message = element(event, "log4j:Message").unwrap_or_default();
+    if let Some(throwable) = element(event, "log4j:Throwable") {
+        message.push('\n');
+        message.push_str(throwable.trim_end());
+    }
+    Some(Line {
+        time,
+        thread: attribute(event, "thread").unwrap_or_default(),
+        level,
+        message,
+        source,
+    })
+}
+
+fn attribute(event: &str, name: &str) -> Option<String> {
+    let start = event.find(&format!(" {name}=\""))? + name.len() + 3;
+    let end = event[start..].find('"')? + start;
+    Some(unescape(&event[start..end]))
+}
+
+fn element(event: &str, name: &str) -> Option<String> {
+    let start = event.find(&format!("<{name}>"))? + name.len() + 2;
+    let end = event[start..].find(&format!("</{name}>"))? + start;
+    let content = event[start..end].trim();
+    Some(
+        match content
+            .strip_prefix("<![CDATA[")
+            .and_then(|content| content.strip_suffix("]]>"))
+        {
+            Some(content) => content.to_string(),
+            None => unescape(content),
+        },
+    )
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn default_level(source: Source) -> Level {
+    match source {
+        Source::Stderr => Level::Error,
+        Source::File | Source::Stdout => Level::Info,
+    }
+}
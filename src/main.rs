@@ -1,24 +1,46 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::collections::HashMap;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use iced::futures::lock::Mutex;
+use iced::widget::column;
 use iced::window::Position;
-use iced::{executor, window, Application, Command, Element, Settings, Theme};
+use iced::{executor, window, Application, Command, Element, Settings, Subscription, Theme};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use single_instance::SingleInstance;
 use tokio::fs;
+use uuid::Uuid;
 
+use crate::launch::LaunchMode;
+use crate::screens::downloads::Downloads;
 use crate::screens::error::Error;
+use crate::screens::setup::Launcher;
 use crate::screens::startup::{load, Startup};
-use crate::screens::{startup, Messages, Screen, Screens};
+use crate::screens::{main, startup, Messages, Screen, Screens};
 
+mod account;
+mod api;
+mod crash;
+mod dependencies;
+mod download;
+mod hash;
+mod install;
 mod instance;
+mod java;
+mod launch;
+mod log;
+mod modpack;
+mod mods;
 mod paths;
+mod process;
+mod profile;
 mod screens;
+mod store;
+mod updates;
 mod widget;
 
 const UUID: &str = "aethon-f082c8ab-df27-4daf-9d09-48ff15ef0204";
@@ -31,15 +53,51 @@ static CONFIG: Lazy<Mutex<Config>> = Lazy::new(|| Mutex::new(Config::default()))
 #[derive(Debug)]
 struct Manager {
     current_screen: Screens,
+    downloads: Downloads,
+    updates: HashMap<Uuid, usize>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct Config {
     launcher_path: Option<PathBuf>,
+    launcher: Option<Launcher>,
+    profile_path: Option<PathBuf>,
+    mojang_url: Option<String>,
+    fabric_url: Option<String>,
+    quilt_url: Option<String>,
+    forge_url: Option<String>,
+    neoforge_url: Option<String>,
+    #[serde(default)]
+    launch_mode: LaunchMode,
+    offline_username: Option<String>,
+    default_account: Option<Uuid>,
+    microsoft_client_id: Option<String>,
+    microsoft_url: Option<String>,
+    xbox_user_url: Option<String>,
+    xsts_url: Option<String>,
+    minecraft_services_url: Option<String>,
+    modrinth_url: Option<String>,
+    curseforge_url: Option<String>,
+    curseforge_api_key: Option<String>,
+    download_concurrency: Option<usize>,
 }
 
 impl Config {
+    fn profile_path(&self) -> Result<PathBuf, Errors> {
+        match &self.profile_path {
+            Some(path) => Ok(path.clone()),
+            None => paths::profile(self.launcher.unwrap_or(Launcher::Custom)),
+        }
+    }
+
+    fn minecraft_dir(&self) -> Result<PathBuf, Errors> {
+        match &self.profile_path {
+            Some(path) => path.parent().map(Path::to_path_buf).ok_or(Errors::NoParent),
+            None => paths::minecraft_dir(self.launcher.unwrap_or(Launcher::Custom)),
+        }
+    }
+
     async fn save(&self) -> Result<(), Errors> {
         let config_path = paths::CONFIG.clone()?;
         fs::write(
@@ -55,7 +113,31 @@ impl Config {
 enum Errors {
     Io(io::ErrorKind),
     Json(String),
+    Http(String),
     NoParent,
+    UnknownVersion(String),
+    MissingLoaderVersion,
+    Installer(String),
+    MissingLibrary(String),
+    Archive(String),
+    ProfilesChanged,
+    NoBackup,
+    MissingClientId,
+    LoginDeclined,
+    LoginExpired,
+    NoXboxAccount,
+    XboxRestricted(u64),
+    NoMinecraftLicense,
+    Login(String),
+    Encryption,
+    Toml(String),
+    NoCompatibleVersion(String),
+    MissingApiKey,
+    DistributionNotAllowed(String),
+    ChecksumMismatch(String),
+    Cancelled,
+    InvalidModpack(String),
+    InvalidFileName(String),
 }
 
 impl Application for Manager {
@@ -68,9 +150,11 @@ impl Application for Manager {
         (
             Manager {
                 current_screen: Startup.into(),
+                downloads: Downloads::default(),
+                updates: HashMap::new(),
             },
             Command::perform(load(), |result| {
-                Messages::Startup(startup::Message::Loaded(result))
+                Messages::Startup(startup::Message::Loaded(Box::new(result)))
             }),
         )
     }
@@ -87,6 +171,14 @@ impl Application for Manager {
             )));
             return Command::none();
         }
+        if let Messages::Downloads(message) = message {
+            return self.downloads.update(message);
+        }
+        if let Messages::Main(main::Message::UpdatesChecked(id, Ok(count))) = &message {
+            if !matches!(self.current_screen, Screens::Main(_)) {
+                self.updates.insert(*id, *count);
+            }
+        }
         match &mut self.current_screen {
             Screens::Startup(screen) => {
                 if let Messages::Startup(message) = message {
@@ -143,6 +235,138 @@ impl Application for Manager {
                     Command::none()
                 }
             }
+            Screens::CreateInstance(screen) => {
+                if let Messages::CreateInstance(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Installing(screen) => {
+                if let Messages::Installing(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Login(screen) => {
+                if let Messages::Login(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Accounts(screen) => {
+                if let Messages::Accounts(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Logs(screen) => {
+                if let Messages::Logs(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Crash(screen) => {
+                if let Messages::Crash(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Import(screen) => {
+                if let Messages::Import(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Updates(screen) => {
+                if let Messages::Updates(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::CurseForge(screen) => {
+                if let Messages::CurseForge(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Modrinth(screen) => {
+                if let Messages::Modrinth(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Problems(screen) => {
+                if let Messages::Problems(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
+            Screens::Mods(screen) => {
+                if let Messages::Mods(message) = message {
+                    let (command, screen) = screen.update(message);
+                    if let Some(screen) = screen {
+                        self.update_screen(screen)
+                    }
+                    command
+                } else {
+                    Command::none()
+                }
+            }
             Screens::Error(screen) => {
                 if let Messages::Error(message) = message {
                     let (command, screen) = screen.update(message);
@@ -158,16 +382,56 @@ impl Application for Manager {
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
-        match &self.current_screen {
+        let screen = match &self.current_screen {
             Screens::Startup(screen) => screen.view(),
             Screens::FolderNotEmptyWarn(screen) => screen.view(),
             Screens::SingleInstanceWarn(screen) => screen.view(),
             Screens::Setup(screen) => screen.view(),
             Screens::Main(screen) => screen.view(),
+            Screens::CreateInstance(screen) => screen.view(),
+            Screens::Installing(screen) => screen.view(),
+            Screens::Login(screen) => screen.view(),
+            Screens::Accounts(screen) => screen.view(),
+            Screens::Logs(screen) => screen.view(),
+            Screens::Crash(screen) => screen.view(),
+            Screens::Mods(screen) => screen.view(),
+            Screens::Problems(screen) => screen.view(),
+            Screens::Modrinth(screen) => screen.view(),
+            Screens::CurseForge(screen) => screen.view(),
+            Screens::Updates(screen) => screen.view(),
+            Screens::Import(screen) => screen.view(),
             Screens::Error(screen) => screen.view(),
+        };
+        match self.downloads.view() {
+            Some(downloads) => column![screen, downloads].into(),
+            None => screen,
         }
     }
 
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let screen = match &self.current_screen {
+            Screens::Startup(screen) => screen.subscription(),
+            Screens::FolderNotEmptyWarn(screen) => screen.subscription(),
+            Screens::SingleInstanceWarn(screen) => screen.subscription(),
+            Screens::Setup(screen) => screen.subscription(),
+            Screens::Main(screen) => screen.subscription(),
+            Screens::CreateInstance(screen) => screen.subscription(),
+            Screens::Installing(screen) => screen.subscription(),
+            Screens::Login(screen) => screen.subscription(),
+            Screens::Accounts(screen) => screen.subscription(),
+            Screens::Logs(screen) => screen.subscription(),
+            Screens::Crash(screen) => screen.subscription(),
+            Screens::Mods(screen) => screen.subscription(),
+            Screens::Problems(screen) => screen.subscription(),
+            Screens::Modrinth(screen) => screen.subscription(),
+            Screens::CurseForge(screen) => screen.subscription(),
+            Screens::Updates(screen) => screen.subscription(),
+            Screens::Import(screen) => screen.subscription(),
+            Screens::Error(screen) => screen.subscription(),
+        };
+        Subscription::batch([screen, self.downloads.subscription()])
+    }
+
     fn theme(&self) -> Theme {
         Theme::Dark
     }
@@ -175,6 +439,15 @@ impl Application for Manager {
 
 impl Manager {
     fn update_screen(&mut self, screen: Screens) {
+        let mut screen = match screen {
+            Screens::Error(error) if error.is_cancelled() => error.previous_screen(),
+            screen => screen,
+        };
+        if let Screens::Main(main) = &mut screen {
+            for (id, count) in self.updates.drain() {
+                main.set_updates(id, count);
+            }
+        }
         self.current_screen = screen;
     }
 }
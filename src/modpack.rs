@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use iced::futures::{stream, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use zip::ZipArchive;
+
+use crate::download::{Hash, Request};
+use crate::instance::{Instance, Loader, ModSource, Platform};
+use crate::{download, Errors};
+
+const INDEX: &str = "modrinth.index.json";
+
+const OVERRIDES: [&str; 2] = ["overrides/", "client-overrides/"];
+
+const MODRINTH_CDN: &str = "https://cdn.modrinth.com/data/";
+
+#[derive(Debug, Clone)]
+pub(crate) struct Modpack {
+    path: PathBuf,
+    pub(crate) index: Index,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Index {
+    game: String,
+    pub(crate) version_id: String,
+    pub(crate) name: String,
+    pub(crate) summary: Option<String>,
+    pub(crate) files: Vec<PackFile>,
+    dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PackFile {
+    pub(crate) path: String,
+    hashes: HashMap<String, String>,
+    env: Option<Env>,
+    downloads: Vec<String>,
+    file_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Env {
+    client: Side,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Side {
+    Required,
+    Optional,
+    Unsupported,
+}
+
+impl Index {
+    pub(crate) fn minecraft_version(&self) -> Option<&str> {
+        self.dependencies.get("minecraft").map(String::as_str)
+    }
+
+    pub(crate) fn loader(&self) -> (Loader, Option<&str>) {
+        [
+            ("fabric-loader", Loader::Fabric),
+            ("quilt-loader", Loader::Quilt),
+            ("forge", Loader::Forge),
+            ("neoforge", Loader::NeoForge),
+        ]
+        .into_iter()
+        .find_map(|(key, loader)| {
+            self.dependencies
+                .get(key)
+                .map(|version| (loader, Some(version.as_str())))
+        })
+        .unwrap_or((Loader::Vanilla, None))
+    }
+}
+
+impl PackFile {
+    pub(crate) fn is_optional(&self) -> bool {
+        self.side() == Side::Optional
+    }
+
+    fn side(&self) -> Side {
+        self.env.as_ref().map_or(Side::Required, |env| env.client)
+    }
+
+    fn request(&self, url: &str, target: &Path) -> Request {
+        Request::new(url, target)
+            .hash(self.hashes.get("sha1").cloned().map(Hash::Sha1))
+            .hash(self.hashes.get("sha512").cloned().map(Hash::Sha512))
+            .size(self.file_size)
+            .stored()
+    }
+
+    fn source(&self) -> Option<ModSource> {
+        let mut parts = self
+            .downloads
+            .iter()
+            .find_map(|url| url.strip_prefix(MODRINTH_CDN))?
+            .split('/');
+        let project_id = parts.next()?;
+        let version_id = parts.nth(1)?;
+        Some(ModSource {
+            platform: Platform::Modrinth,
+            project_id: project_id.to_string(),
+            version_id: version_id.to_string(),
+            pinned: false,
+        })
+    }
+}
+
+pub(crate) async fn read(path: PathBuf) -> Result<Modpack, Errors> {
+    tokio::task::spawn_blocking(move || read_blocking(path))
+        .await
+        .map_err(|_| Errors::Io(io::ErrorKind::Other))?
+}
+
+fn read_blocking(path: PathBuf) -> Result<Modpack, Errors> {
+    let mut archive = ZipArchive::new(File::open(&path).map_err(|error| Errors::Io(error.kind()))?)
+        .map_err(|error| Errors::Archive(error.to_string()))?;
+    let index = serde_json::from_reader::<_, Index>(
+        archive
+            .by_name(INDEX)
+            .map_err(|_| Errors::InvalidModpack(format!("{INDEX} is missing")))?,
+    )
+    .map_err(|error| Errors::Json(error.to_string()))?;
+    if index.game != "minecraft" {
+        return Err(Errors::InvalidModpack(format!(
+            "the pack is for {}, not Minecraft",
+            index.game
+        )));
+    }
+    if index.minecraft_version().is_none() {
+        return Err(Errors::InvalidModpack(String::from(
+            "the pack does not specify a Minecraft version",
+        )));
+    }
+    if let Some(file) = index.files.iter().find(|file| !is_enclosed(&file.path)) {
+        return Err(Errors::InvalidModpack(format!(
+            "{} points outside of the instance",
+            file.path
+        )));
+    }
+    Ok(Modpack { path, index })
+}
+
+pub(crate) async fn import(
+    modpack: Modpack,
+    optional: HashSet<String>,
+) -> Result<Instance, Errors> {
+    let index = &modpack.index;
+    let (loader, loader_version) = index.loader();
+    let mut instance = Instance::new(
+        &index.name,
+        index.minecraft_version().unwrap_or_default(),
+        loader,
+        loader_version,
+    )
+    .await?;
+    match populate(&modpack, &optional, &mut instance).await {
+        Ok(()) => Ok(instance),
+        Err(error) => {
+            let _ = instance.delete().await;
+            Err(error)
+        }
+    }
+}
+
+async fn populate(
+    modpack: &Modpack,
+    optional: &HashSet<String>,
+    instance: &mut Instance,
+) -> Result<(), Errors> {
+    let files = modpack
+        .index
+        .files
+        .iter()
+        .filter(|file| match file.side() {
+            Side::Required => true,
+            Side::Optional => optional.contains(&file.path),
+            Side::Unsupported => false,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let root = instance.path().clone();
+    stream::iter(files.clone())
+        .map(|file| fetch(file, root.clone()))
+        .buffer_unordered(download::concurrency().await)
+        .try_collect::<()>()
+        .await?;
+
+    let (path, target) = (modpack.path.clone(), root.clone());
+    tokio::task::spawn_blocking(move || extract_overrides(&path, &target))
+        .await
+        .map_err(|_| Errors::Io(io::ErrorKind::Other))??;
+
+    for file in files {
+        let Some(jar_name) = file.path.strip_prefix("mods/") else {
+            continue;
+        };
+        if let Some(source) = file.source() {
+            instance.set_mod_source(jar_name, Some(source)).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn fetch(file: PackFile, root: PathBuf) -> Result<(), Errors> {
+    let target = root.join(&file.path);
+    let mut result = Err(Errors::InvalidModpack(format!(
+        "{} has no download",
+        file.path
+    )));
+    for url in &file.downloads {
+        result = download::fetch(file.request(url, &target)).await;
+        if matches!(result, Ok(()) | Err(Errors::Cancelled)) {
+            break;
+        }
+    }
+    result
+}
+
+fn extract_overrides(path: &Path, root: &Path) -> Result<(), Errors> {
+    let mut archive = ZipArchive::new(File::open(path).map_err(|error| Errors::Io(error.kind()))?)
+        .map_err(|error| Errors::Archive(error.to_string()))?;
+    for prefix in OVERRIDES {
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|error| Errors::Archive(error.to_string()))?;
+            let Some(relative) = file
+                .enclosed_name()
+                .and_then(|name| name.strip_prefix(prefix).ok())
+                .map(Path::to_path_buf)
+            else {
+                continue;
+            };
+            let target = root.join(relative);
+            if file.is_dir() {
+                std::fs::create_dir_all(&target).map_err(|error| Errors::Io(error.kind()))?;
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|error| Errors::Io(error.kind()))?;
+            }
+            match std::fs::remove_file(&target) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                    return Err(Errors::Io(error.kind()));
+                }
+                _ => {}
+            }
+            let mut output = File::create(&target).map_err(|error| Errors::Io(error.kind()))?;
+            io::copy(&mut file, &mut output).map_err(|error| Errors::Io(error.kind()))?;
+        }
+    }
+    Ok(())
+}
+
+fn is_enclosed(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
@@ -0,0 +1,729 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{Cursor, ErrorKind, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::fs;
+use zip::ZipArchive;
+
+use crate::{store, Errors};
+
+const FABRIC: &str = "fabric.mod.json";
+
+const QUILT: &str = "quilt.mod.json";
+
+const FORGE: &str = "META-INF/mods.toml";
+
+const NEOFORGE: &str = "META-INF/neoforge.mods.toml";
+
+const LEGACY: &str = "mcmod.info";
+
+const MANIFEST: &str = "META-INF/MANIFEST.MF";
+
+const DISABLED: &str = ".disabled";
+
+const NESTED: [&str; 2] = ["META-INF/jars/", "META-INF/jarjar/"];
+
+const MAX_NESTING: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Fabric,
+    Quilt,
+    Forge,
+    NeoForge,
+    Legacy,
+    Unknown,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Format::Fabric => "Fabric",
+            Format::Quilt => "Quilt",
+            Format::Forge => "Forge",
+            Format::NeoForge => "NeoForge",
+            Format::Legacy => "Legacy Forge",
+            Format::Unknown => "Unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Environment {
+    #[default]
+    Both,
+    Client,
+    Server,
+}
+
+impl Environment {
+    fn parse(environment: &str) -> Environment {
+        match environment.to_ascii_lowercase().as_str() {
+            "client" => Environment::Client,
+            "server" | "dedicated_server" => Environment::Server,
+            _ => Environment::Both,
+        }
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Environment::Both => "Client and server",
+            Environment::Client => "Client",
+            Environment::Server => "Server",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DependencyKind {
+    Required,
+    Optional,
+    Recommended,
+    Breaks,
+    Conflicts,
+}
+
+impl Display for DependencyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DependencyKind::Required => "requires",
+            DependencyKind::Optional => "optionally uses",
+            DependencyKind::Recommended => "recommends",
+            DependencyKind::Breaks => "breaks",
+            DependencyKind::Conflicts => "conflicts with",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Dependency {
+    pub(crate) id: String,
+    pub(crate) kind: DependencyKind,
+    pub(crate) versions: Vec<String>,
+    pub(crate) side: Environment,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ModInfo {
+    pub(crate) path: PathBuf,
+    pub(crate) enabled: bool,
+    pub(crate) format: Format,
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) authors: Vec<String>,
+    pub(crate) description: String,
+    pub(crate) icon: Option<Vec<u8>>,
+    pub(crate) environment: Environment,
+    pub(crate) dependencies: Vec<Dependency>,
+    pub(crate) provides: Vec<String>,
+    pub(crate) mixins: Vec<String>,
+    pub(crate) bundled: Vec<ModInfo>,
+}
+
+impl ModInfo {
+    fn unknown(path: &Path) -> Self {
+        let file_name = file_name(path);
+        let enabled = !file_name.ends_with(DISABLED);
+        let file_name = file_name.trim_end_matches(DISABLED);
+        let stem = file_name
+            .strip_suffix(".jar")
+            .unwrap_or(file_name)
+            .to_string();
+        ModInfo {
+            path: path.to_path_buf(),
+            enabled,
+            format: Format::Unknown,
+            id: stem.to_lowercase(),
+            name: stem,
+            version: String::new(),
+            authors: vec![],
+            description: String::new(),
+            icon: None,
+            environment: Environment::Both,
+            dependencies: vec![],
+            provides: vec![],
+            mixins: vec![],
+            bundled: vec![],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ModsToml {
+    #[serde(rename = "logoFile")]
+    logo_file: Option<String>,
+    #[serde(default)]
+    mods: Vec<TomlMod>,
+    #[serde(default)]
+    dependencies: HashMap<String, Vec<TomlDependency>>,
+    #[serde(default)]
+    mixins: Vec<TomlMixin>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TomlMod {
+    mod_id: String,
+    version: Option<String>,
+    display_name: Option<String>,
+    description: Option<String>,
+    authors: Option<String>,
+    logo_file: Option<String>,
+    display_test: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TomlMixin {
+    config: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TomlDependency {
+    mod_id: String,
+    mandatory: Option<bool>,
+    #[serde(rename = "type")]
+    dependency_type: Option<String>,
+    version_range: Option<String>,
+    side: Option<String>,
+}
+
+pub(crate) fn mods_dir(instance_path: &Path) -> PathBuf {
+    instance_path.join("mods")
+}
+
+pub(crate) async fn scan(mods_path: PathBuf) -> Result<Vec<ModInfo>, Errors> {
+    tokio::task::spawn_blocking(move || scan_blocking(&mods_path))
+        .await
+        .map_err(|_| Errors::Io(ErrorKind::Other))?
+}
+
+fn scan_blocking(mods_path: &Path) -> Result<Vec<ModInfo>, Errors> {
+    if !mods_path.exists() {
+        return Ok(vec![]);
+    }
+    let mut mods = std::fs::read_dir(mods_path)
+        .map_err(|error| Errors::Io(error.kind()))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let file_name = file_name(path);
+            path.is_file()
+                && (file_name.ends_with(".jar") || file_name.ends_with(&format!(".jar{DISABLED}")))
+        })
+        .map(|path| inspect(&path).unwrap_or_else(|_| ModInfo::unknown(&path)))
+        .collect::<Vec<_>>();
+    mods.sort_by_key(|info| info.name.to_lowercase());
+    Ok(mods)
+}
+
+pub(crate) async fn set_enabled(paths: Vec<PathBuf>, enabled: bool) -> Result<(), Errors> {
+    for path in paths {
+        let file_name = file_name(&path);
+        let target = match (enabled, file_name.strip_suffix(DISABLED)) {
+            (true, Some(enabled_name)) => path.with_file_name(enabled_name),
+            (false, None) => path.with_file_name(format!("{file_name}{DISABLED}")),
+            _ => continue,
+        };
+        if fs::try_exists(&target).await.unwrap_or(true) {
+            return Err(Errors::Io(ErrorKind::AlreadyExists));
+        }
+        fs::rename(path, target)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn delete(paths: Vec<PathBuf>) -> Result<(), Errors> {
+    for path in paths {
+        store::unlink(&path).await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn remove(mods_path: &Path, jar_name: &str) -> Result<(), Errors> {
+    for path in [
+        mods_path.join(jar_name),
+        mods_path.join(format!("{jar_name}{DISABLED}")),
+    ] {
+        match store::unlink(&path).await {
+            Err(Errors::Io(ErrorKind::NotFound)) | Ok(()) => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) async fn add(
+    mods_path: PathBuf,
+    files: Vec<PathBuf>,
+    replace: bool,
+) -> Result<(), Errors> {
+    fs::create_dir_all(&mods_path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    for file in files {
+        let jar_name = file_name(&file);
+        if is_installed(&mods_path, &jar_name) {
+            if !replace {
+                return Err(Errors::Io(ErrorKind::AlreadyExists));
+            }
+            remove(&mods_path, &jar_name).await?;
+        }
+        store::add(&file, &mods_path.join(jar_name)).await?;
+    }
+    Ok(())
+}
+
+pub(crate) fn installed(mods_path: &Path, files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .map(|file| file_name(file))
+        .filter(|jar_name| is_installed(mods_path, jar_name))
+        .collect()
+}
+
+fn is_installed(mods_path: &Path, jar_name: &str) -> bool {
+    mods_path.join(jar_name).exists() || mods_path.join(format!("{jar_name}{DISABLED}")).exists()
+}
+
+pub(crate) fn inspect(path: &Path) -> Result<ModInfo, Errors> {
+    let mut archive = ZipArchive::new(File::open(path).map_err(|error| Errors::Io(error.kind()))?)
+        .map_err(|error| Errors::Archive(error.to_string()))?;
+    let mut info = ModInfo::unknown(path);
+    let icon = read_metadata(&mut archive, &mut info, 0)?;
+    info.icon = icon.and_then(|icon| read_bytes(&mut archive, icon.trim_start_matches('/')));
+    Ok(info)
+}
+
+fn read_metadata<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    info: &mut ModInfo,
+    depth: usize,
+) -> Result<Option<String>, Errors> {
+    let icon = if let Some(content) = read(archive, FABRIC) {
+        parse_fabric(info, &json(&content)?)
+    } else if let Some(content) = read(archive, QUILT) {
+        parse_quilt(info, &json(&content)?)
+    } else if let Some(content) = read(archive, NEOFORGE) {
+        info.format = Format::NeoForge;
+        let version = manifest_version(archive);
+        parse_mods_toml(info, &content, version)?
+    } else if let Some(content) = read(archive, FORGE) {
+        info.format = Format::Forge;
+        let version = manifest_version(archive);
+        parse_mods_toml(info, &content, version)?
+    } else if let Some(content) = read(archive, LEGACY) {
+        parse_legacy(info, &json(&content)?)
+    } else {
+        None
+    };
+    info.mixins.extend(manifest_mixins(archive));
+
+    if depth < MAX_NESTING {
+        let nested = archive
+            .file_names()
+            .filter(|name| {
+                name.ends_with(".jar") && NESTED.iter().any(|prefix| name.starts_with(prefix))
+            })
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        for name in nested {
+            let Some(content) = read_bytes(archive, &name) else {
+                continue;
+            };
+            let Ok(mut nested_archive) = ZipArchive::new(Cursor::new(content)) else {
+                continue;
+            };
+            let mut bundled = ModInfo::unknown(Path::new(&name));
+            if read_metadata(&mut nested_archive, &mut bundled, depth + 1).is_ok()
+                && bundled.format != Format::Unknown
+            {
+                info.bundled.append(&mut bundled.bundled);
+                info.bundled.push(bundled);
+            }
+        }
+    }
+    Ok(icon)
+}
+
+fn parse_fabric(info: &mut ModInfo, value: &Value) -> Option<String> {
+    info.format = Format::Fabric;
+    set(&mut info.id, value.get("id"));
+    set(&mut info.name, value.get("name").or(value.get("id")));
+    set(&mut info.version, value.get("version"));
+    set(&mut info.description, value.get("description"));
+    info.authors = people(value.get("authors"));
+    info.environment = value
+        .get("environment")
+        .and_then(Value::as_str)
+        .map(Environment::parse)
+        .unwrap_or_default();
+    info.provides = strings(value.get("provides"));
+    info.mixins = value
+        .get("mixins")
+        .and_then(Value::as_array)
+        .map(|mixins| {
+            mixins
+                .iter()
+                .filter_map(|mixin| match mixin {
+                    Value::String(config) => Some(config.clone()),
+                    mixin => mixin.get("config")?.as_str().map(str::to_string),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    for (key, kind) in [
+        ("depends", DependencyKind::Required),
+        ("recommends", DependencyKind::Recommended),
+        ("suggests", DependencyKind::Optional),
+        ("breaks", DependencyKind::Breaks),
+        ("conflicts", DependencyKind::Conflicts),
+    ] {
+        if let Some(dependencies) = value.get(key).and_then(Value::as_object) {
+            info.dependencies
+                .extend(dependencies.iter().map(|(id, versions)| Dependency {
+                    id: id.clone(),
+                    kind,
+                    versions: strings(Some(versions)),
+                    side: Environment::Both,
+                }));
+        }
+    }
+    icon(value.get("icon"))
+}
+
+fn parse_quilt(info: &mut ModInfo, value: &Value) -> Option<String> {
+    info.format = Format::Quilt;
+    let loader = value.get("quilt_loader")?;
+    let metadata = loader.get("metadata");
+    set(&mut info.id, loader.get("id"));
+    set(&mut info.version, loader.get("version"));
+    set(
+        &mut info.name,
+        metadata
+            .and_then(|metadata| metadata.get("name"))
+            .or(loader.get("id")),
+    );
+    set(
+        &mut info.description,
+        metadata.and_then(|metadata| metadata.get("description")),
+    );
+    info.authors = match metadata.and_then(|metadata| metadata.get("contributors")) {
+        Some(Value::Object(contributors)) => contributors.keys().cloned().collect(),
+        contributors => people(contributors),
+    };
+    info.environment = value
+        .get("minecraft")
+        .and_then(|minecraft| minecraft.get("environment"))
+        .and_then(Value::as_str)
+        .map(Environment::parse)
+        .unwrap_or_default();
+    info.provides = loader
+        .get("provides")
+        .and_then(Value::as_array)
+        .map(|provides| {
+            provides
+                .iter()
+                .filter_map(|provide| match provide {
+                    Value::String(id) => Some(id.clone()),
+                    provide => provide.get("id")?.as_str().map(str::to_string),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    info.mixins = strings(value.get("mixin"));
+    for (key, kind) in [
+        ("depends", DependencyKind::Required),
+        ("breaks", DependencyKind::Breaks),
+    ] {
+        let Some(dependencies) = loader.get(key).and_then(Value::as_array) else {
+            continue;
+        };
+        info.dependencies.extend(
+            dependencies
+                .iter()
+                .filter_map(|dependency| match dependency {
+                    Value::String(id) => Some(Dependency {
+                        id: id.clone(),
+                        kind,
+                        versions: vec![],
+                        side: Environment::Both,
+                    }),
+                    dependency => {
+                        let optional = dependency
+                            .get("optional")
+                            .and_then(Value::as_bool)
+                            .unwrap_or(false);
+                        Some(Dependency {
+                            id: dependency.get("id")?.as_str()?.to_string(),
+                            kind: if optional && kind == DependencyKind::Required {
+                                DependencyKind::Optional
+                            } else {
+                                kind
+                            },
+                            versions: strings(dependency.get("versions")),
+                            side: Environment::Both,
+                        })
+                    }
+                }),
+        );
+    }
+    icon(metadata.and_then(|metadata| metadata.get("icon")))
+}
+
+fn parse_mods_toml(
+    info: &mut ModInfo,
+    content: &str,
+    jar_version: Option<String>,
+) -> Result<Option<String>, Errors> {
+    let toml =
+        toml::from_str::<ModsToml>(content).map_err(|error| Errors::Toml(error.to_string()))?;
+    let Some(first) = toml.mods.first() else {
+        return Ok(None);
+    };
+    info.id = first.mod_id.clone();
+    info.name = first
+        .display_name
+        .clone()
+        .unwrap_or_else(|| first.mod_id.clone());
+    info.version = match &first.version {
+        Some(version) if version.contains("${file.jarVersion}") => {
+            version.replace("${file.jarVersion}", jar_version.as_deref().unwrap_or(""))
+        }
+        Some(version) => version.clone(),
+        None => jar_version.unwrap_or_default(),
+    };
+    info.description = first
+        .description
+        .clone()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    info.authors = first
+        .authors
+        .iter()
+        .flat_map(|authors| authors.split(','))
+        .map(|author| author.trim().to_string())
+        .filter(|author| !author.is_empty())
+        .collect();
+    info.provides = toml.mods[1..]
+        .iter()
+        .map(|other| other.mod_id.clone())
+        .collect();
+    let dependencies = toml
+        .dependencies
+        .get(&first.mod_id)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    info.environment = match first.display_test.as_deref() {
+        Some("IGNORE_ALL_VERSION") => Environment::Client,
+        Some("IGNORE_SERVER_VERSION") => Environment::Server,
+        _ => dependencies
+            .iter()
+            .find(|dependency| {
+                matches!(
+                    dependency.mod_id.as_str(),
+                    "minecraft" | "forge" | "neoforge"
+                )
+            })
+            .and_then(|dependency| dependency.side.as_deref())
+            .map(Environment::parse)
+            .unwrap_or_default(),
+    };
+    info.mixins = toml
+        .mixins
+        .iter()
+        .map(|mixin| mixin.config.clone())
+        .collect();
+    info.dependencies = dependencies
+        .iter()
+        .map(|dependency| Dependency {
+            id: dependency.mod_id.clone(),
+            kind: match dependency.dependency_type.as_deref() {
+                Some("required") => DependencyKind::Required,
+                Some("optional") => DependencyKind::Optional,
+                Some("incompatible") => DependencyKind::Breaks,
+                Some("discouraged") => DependencyKind::Conflicts,
+                _ if dependency.mandatory.unwrap_or(true) => DependencyKind::Required,
+                _ => DependencyKind::Optional,
+            },
+            versions: dependency.version_range.clone().into_iter().collect(),
+            side: dependency
+                .side
+                .as_deref()
+                .map(Environment::parse)
+                .unwrap_or_default(),
+        })
+        .collect();
+    Ok(first.logo_file.clone().or(toml.logo_file))
+}
+
+fn parse_legacy(info: &mut ModInfo, value: &Value) -> Option<String> {
+    info.format = Format::Legacy;
+    let list = match value {
+        Value::Array(list) => list,
+        value => value.get("modList")?.as_array()?,
+    };
+    let first = list.first()?;
+    set(&mut info.id, first.get("modid"));
+    set(&mut info.name, first.get("name").or(first.get("modid")));
+    set(&mut info.version, first.get("version"));
+    set(&mut info.description, first.get("description"));
+    info.authors = strings(first.get("authorList").or(first.get("authors")));
+    info.provides = list[1..]
+        .iter()
+        .filter_map(|other| Some(other.get("modid")?.as_str()?.to_string()))
+        .collect();
+    info.dependencies = strings(first.get("requiredMods"))
+        .into_iter()
+        .map(|dependency| {
+            let (id, versions) = dependency.split_once('@').unwrap_or((&dependency, ""));
+            Dependency {
+                id: id.to_string(),
+                kind: DependencyKind::Required,
+                versions: (!versions.is_empty())
+                    .then(|| versions.to_string())
+                    .into_iter()
+                    .collect(),
+                side: Environment::Both,
+            }
+        })
+        .collect();
+    first
+        .get("logoFile")
+        .and_then(Value::as_str)
+        .filter(|logo| !logo.is_empty())
+        .map(str::to_string)
+}
+
+fn read<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<String> {
+    read_bytes(archive, name).map(|content| String::from_utf8_lossy(&content).to_string())
+}
+
+fn read_bytes<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<Vec<u8>> {
+    let mut file = archive.by_name(name).ok()?;
+    let mut content = vec![];
+    file.read_to_end(&mut content).ok()?;
+    Some(content)
+}
+
+fn manifest_version<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<String> {
+    read(archive, MANIFEST)?.lines().find_map(|line| {
+        line.strip_prefix("Implementation-Version:")
+            .map(|version| version.trim().to_string())
+    })
+}
+
+fn manifest_mixins<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Vec<String> {
+    read(archive, MANIFEST)
+        .and_then(|manifest| {
+            manifest.lines().find_map(|line| {
+                line.strip_prefix("MixinConfigs:").map(|configs| {
+                    configs
+                        .split(',')
+                        .map(|config| config.trim().to_string())
+                        .filter(|config| !config.is_empty())
+                        .collect()
+                })
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn json(content: &str) -> Result<Value, Errors> {
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .or_else(|_| serde_json::from_str(&content.replace(['\n', '\r', '\t'], " ")))
+        .map_err(|error| Errors::Json(error.to_string()))
+}
+
+fn set(field: &mut String, value: Option<&Value>) {
+    if let Some(value) = value.and_then(Value::as_str) {
+        *field = value.trim().to_string();
+    }
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(value)) => vec![value.clone()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn people(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|people| {
+            people
+                .iter()
+                .filter_map(|person| match person {
+                    Value::String(name) => Some(name.clone()),
+                    person => person.get("name")?.as_str().map(str::to_string),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn icon(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(icon) => Some(icon.clone()),
+        Value::Object(icons) => icons
+            .iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+            .and_then(|(_, icon)| icon.as_str().map(str::to_string)),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none()
+}
+
+pub(crate) fn jar_name(path: &Path) -> String {
+    file_name(path).trim_end_matches(DISABLED).to_string()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn removes_read_only_mods() {
+        let mods_path = std::env::temp_dir().join(format!("aethon-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&mods_path).await.unwrap();
+        for jar_name in ["a.jar", "b.jar.disabled"] {
+            let path = mods_path.join(jar_name);
+            fs::write(&path, b"jar").await.unwrap();
+            let mut permissions = fs::metadata(&path).await.unwrap().permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(&path, permissions).await.unwrap();
+        }
+
+        remove(&mods_path, "b.jar").await.unwrap();
+        delete(vec![mods_path.join("a.jar")]).await.unwrap();
+
+        assert!(!mods_path.join("a.jar").exists());
+        assert!(!mods_path.join("b.jar.disabled").exists());
+        fs::remove_dir_all(&mods_path).await.unwrap();
+    }
+}
@@ -1,10 +1,12 @@
 use std::clone::Clone;
 use std::env;
+use std::ffi::OsStr;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
 use once_cell::sync::Lazy;
 
+use crate::screens::setup::Launcher;
 use crate::Errors;
 
 pub(crate) static CURRENT_DIR: Lazy<Result<PathBuf, Errors>> = Lazy::new(|| {
@@ -19,8 +21,94 @@ pub(crate) static CONFIG: Lazy<Result<PathBuf, Errors>> =
 pub(crate) static INSTANCES: Lazy<Result<PathBuf, Errors>> =
     Lazy::new(|| CURRENT_DIR.clone().map(|path| path.join("instances")));
 
-pub(crate) static PROFILE: Lazy<Result<PathBuf, Errors>> = Lazy::new(|| {
-    dirs::config_dir()
-        .map(|path| path.join(".minecraft/launcher_profiles.json"))
+pub(crate) static CACHE: Lazy<Result<PathBuf, Errors>> =
+    Lazy::new(|| CURRENT_DIR.clone().map(|path| path.join("cache")));
+
+pub(crate) static STORE: Lazy<Result<PathBuf, Errors>> =
+    Lazy::new(|| CURRENT_DIR.clone().map(|path| path.join("store")));
+
+pub(crate) static BACKUPS: Lazy<Result<PathBuf, Errors>> =
+    Lazy::new(|| CURRENT_DIR.clone().map(|path| path.join("backups")));
+
+pub(crate) static ACCOUNTS: Lazy<Result<PathBuf, Errors>> =
+    Lazy::new(|| CURRENT_DIR.clone().map(|path| path.join("accounts.dat")));
+
+pub(crate) static ACCOUNTS_KEY: Lazy<Result<PathBuf, Errors>> = Lazy::new(|| {
+    dirs::data_local_dir()
+        .map(|path| path.join("aethon").join("accounts.key"))
         .ok_or(Errors::Io(ErrorKind::NotFound))
 });
+
+pub(crate) static LEGACY_ACCOUNTS_KEY: Lazy<Result<PathBuf, Errors>> =
+    Lazy::new(|| CURRENT_DIR.clone().map(|path| path.join("accounts.key")));
+
+pub(crate) static CRASH_RULES: Lazy<Result<PathBuf, Errors>> = Lazy::new(|| {
+    CURRENT_DIR
+        .clone()
+        .map(|path| path.join("crash_rules.json"))
+});
+
+const STORE_PACKAGE: &str = "Packages/Microsoft.4297127D64EC6_8wekyb3d8bbwe/LocalCache/Local";
+
+pub(crate) fn open(target: impl AsRef<OsStr>) -> Result<(), Errors> {
+    #[cfg(windows)]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let program = "xdg-open";
+    let mut child = std::process::Command::new(program)
+        .arg(target)
+        .spawn()
+        .map_err(|error| Errors::Io(error.kind()))?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+pub(crate) fn minecraft_dir(launcher: Launcher) -> Result<PathBuf, Errors> {
+    match launcher {
+        Launcher::Store => {
+            let package = dirs::data_local_dir()
+                .map(|path| path.join(STORE_PACKAGE).join(".minecraft"))
+                .filter(|path| path.exists());
+            package.map_or_else(default_minecraft_dir, Ok)
+        }
+        Launcher::Flatpak => dirs::home_dir()
+            .map(|path| path.join(".var/app/com.mojang.Minecraft/.minecraft"))
+            .ok_or(Errors::Io(ErrorKind::NotFound)),
+        Launcher::Legacy
+        | Launcher::Package
+        | Launcher::Aur
+        | Launcher::Tarball
+        | Launcher::Custom => default_minecraft_dir(),
+    }
+}
+
+pub(crate) fn profile(launcher: Launcher) -> Result<PathBuf, Errors> {
+    let file_name = match launcher {
+        Launcher::Store => "launcher_profiles_microsoft_store.json",
+        _ => "launcher_profiles.json",
+    };
+    minecraft_dir(launcher).map(|path| path.join(file_name))
+}
+
+#[cfg(windows)]
+fn default_minecraft_dir() -> Result<PathBuf, Errors> {
+    dirs::config_dir()
+        .map(|path| path.join(".minecraft"))
+        .ok_or(Errors::Io(ErrorKind::NotFound))
+}
+
+#[cfg(target_os = "macos")]
+fn default_minecraft_dir() -> Result<PathBuf, Errors> {
+    dirs::config_dir()
+        .map(|path| path.join("minecraft"))
+        .ok_or(Errors::Io(ErrorKind::NotFound))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn default_minecraft_dir() -> Result<PathBuf, Errors> {
+    dirs::home_dir()
+        .map(|path| path.join(".minecraft"))
+        .ok_or(Errors::Io(ErrorKind::NotFound))
+}
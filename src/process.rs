@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use iced::futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use iced::futures::channel::oneshot;
+use iced::futures::lock::Mutex;
+use iced::futures::{future, SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::process::Child;
+use uuid::Uuid;
+
+use crate::log::Source;
+
+const OUTPUT_LIMIT: usize = 10000;
+
+static EVENTS: Lazy<(UnboundedSender<Event>, Mutex<UnboundedReceiver<Event>>)> = Lazy::new(|| {
+    let (sender, receiver) = unbounded();
+    (sender, Mutex::new(receiver))
+});
+
+static OUTPUT: Lazy<Mutex<HashMap<Uuid, Output>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static KILLERS: Lazy<Mutex<HashMap<Uuid, oneshot::Sender<()>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+pub(crate) enum Event {
+    Started(Uuid),
+    Exited(Uuid, Run),
+    Crashed(Uuid, Run),
+    Killed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Run {
+    pub(crate) exit_code: Option<i32>,
+    pub(crate) duration: Duration,
+    pub(crate) ended_at: u64,
+    pub(crate) killed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Output {
+    dropped: usize,
+    lines: VecDeque<(Source, String)>,
+}
+
+impl Run {
+    pub(crate) fn crashed(&self) -> bool {
+        !self.killed && self.exit_code != Some(0)
+    }
+
+    pub(crate) fn started_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.ended_at) - self.duration
+    }
+}
+
+pub(crate) async fn supervise(id: Uuid, mut child: Child) {
+    OUTPUT.lock().await.insert(id, Output::default());
+    let mut captures = vec![];
+    if let Some(stdout) = child.stdout.take() {
+        captures.push(tokio::spawn(capture(
+            id,
+            Source::Stdout,
+            BufReader::new(stdout),
+        )));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        captures.push(tokio::spawn(capture(
+            id,
+            Source::Stderr,
+            BufReader::new(stderr),
+        )));
+    }
+
+    let (killer, kill) = oneshot::channel();
+    KILLERS.lock().await.insert(id, killer);
+    let _ = EVENTS.0.unbounded_send(Event::Started(id));
+
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let (status, killed) = tokio::select! {
+            status = child.wait() => (status, false),
+            Ok(()) = kill => {
+                let _ = child.start_kill();
+                (child.wait().await, true)
+            }
+        };
+        KILLERS.lock().await.remove(&id);
+        for capture in captures {
+            let _ = capture.await;
+        }
+
+        let exit_code = status.ok().and_then(|status| status.code());
+        let run = Run {
+            exit_code,
+            duration: started.elapsed(),
+            ended_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            killed,
+        };
+        let _ = EVENTS.0.unbounded_send(if run.crashed() {
+            Event::Crashed(id, run)
+        } else {
+            Event::Exited(id, run)
+        });
+    });
+}
+
+pub(crate) async fn running() -> HashSet<Uuid> {
+    KILLERS.lock().await.keys().copied().collect()
+}
+
+pub(crate) async fn kill(id: Uuid) {
+    if let Some(killer) = KILLERS.lock().await.remove(&id) {
+        let _ = killer.send(());
+    }
+}
+
+pub(crate) async fn forget(id: Uuid) {
+    OUTPUT.lock().await.remove(&id);
+}
+
+pub(crate) async fn is_captured(id: Uuid) -> bool {
+    OUTPUT.lock().await.contains_key(&id)
+}
+
+pub(crate) async fn output(id: Uuid, cursor: usize) -> (usize, Vec<(Source, String)>) {
+    match OUTPUT.lock().await.get(&id) {
+        Some(output) => {
+            let total = output.dropped + output.lines.len();
+            let skip = if cursor > total {
+                0
+            } else {
+                cursor.saturating_sub(output.dropped)
+            };
+            (total, output.lines.iter().skip(skip).cloned().collect())
+        }
+        None => (cursor, vec![]),
+    }
+}
+
+async fn capture(id: Uuid, source: Source, reader: impl AsyncBufRead + Unpin) {
+    let mut lines = reader.lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let mut outputs = OUTPUT.lock().await;
+        let Some(output) = outputs.get_mut(&id) else {
+            break;
+        };
+        output.lines.push_back((source, line));
+        if output.lines.len() > OUTPUT_LIMIT {
+            output.lines.pop_front();
+            output.dropped += 1;
+        }
+    }
+}
+
+pub(crate) fn events() -> Subscription<Event> {
+    struct Events;
+
+    subscription::channel(
+        std::any::TypeId::of::<Events>(),
+        100,
+        |mut output| async move {
+            let mut receiver = EVENTS.1.lock().await;
+            while let Some(event) = receiver.next().await {
+                let _ = output.send(event).await;
+            }
+
+            future::pending().await
+        },
+    )
+}
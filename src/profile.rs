@@ -0,0 +1,230 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use iced::futures::lock::Mutex;
+use once_cell::sync::Lazy;
+use serde_json::{json, Map, Value};
+use tokio::fs;
+
+use crate::instance::Instance;
+use crate::{paths, Errors, CONFIG};
+
+const LEGACY_KEY: &str = "aethon";
+
+const ATTEMPTS: usize = 5;
+
+const KEPT_BACKUPS: usize = 20;
+
+const DEFAULT_ICON: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAIAAAACAAQMAAAD58POIAAAABlBMVEUAAAD4APit1uGJAAAAI0lEQVRIx2P4DwUMMDAqMCowKjAqQKTAaDCMCowKjAqQKQAABpD8LlM5SL4AAAAASUVORK5CYII";
+
+pub(crate) const ICONS: [&str; 24] = [
+    "Bookshelf",
+    "Brick",
+    "Cake",
+    "Chest",
+    "Cobblestone",
+    "Crafting_Table",
+    "Creeper_Head",
+    "Diamond_Block",
+    "Dirt",
+    "Emerald_Block",
+    "Enchanting_Table",
+    "End_Stone",
+    "Furnace",
+    "Glowstone",
+    "Gold_Block",
+    "Grass",
+    "Iron_Block",
+    "Log_Oak",
+    "Netherrack",
+    "Obsidian",
+    "Planks_Oak",
+    "Redstone_Block",
+    "Stone",
+    "TNT",
+];
+
+static LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+pub(crate) async fn add_profile(instance: &Instance) -> Result<(), Errors> {
+    edit_profiles(|profiles| write_profile(profiles, instance)).await
+}
+
+pub(crate) async fn update_profile(instance: &Instance) -> Result<(), Errors> {
+    let path = CONFIG.lock().await.profile_path()?;
+    if !fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(());
+    }
+    edit_profiles(|profiles| {
+        if let Some(profile) = profiles.get_mut(&key(instance)) {
+            fill_profile(profile, instance);
+        }
+    })
+    .await
+}
+
+pub(crate) async fn remove_profile(instance: &Instance) -> Result<(), Errors> {
+    let path = CONFIG.lock().await.profile_path()?;
+    if !fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(());
+    }
+    edit_profiles(|profiles| {
+        profiles.remove(&key(instance));
+    })
+    .await
+}
+
+pub(crate) async fn sync_profiles(instances: Vec<Instance>) -> Result<(), Errors> {
+    edit_profiles(|profiles| {
+        profiles.remove(LEGACY_KEY);
+        for instance in &instances {
+            write_profile(profiles, instance);
+        }
+    })
+    .await
+}
+
+pub(crate) async fn restore_profiles() -> Result<(), Errors> {
+    let _guard = LOCK.lock().await;
+    let path = CONFIG.lock().await.profile_path()?;
+    let backup = backups(&path).await?.pop().ok_or(Errors::NoBackup)?;
+    let contents = fs::read_to_string(&backup)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    if let Ok(current) = fs::read_to_string(&path).await {
+        self::backup(&path, &current).await?;
+    }
+    write_atomically(&path, &contents).await?;
+    fs::remove_file(backup)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+async fn edit_profiles(mut edit: impl FnMut(&mut Map<String, Value>)) -> Result<(), Errors> {
+    let _guard = LOCK.lock().await;
+    let path = CONFIG.lock().await.profile_path()?;
+    for _ in 0..ATTEMPTS {
+        let original = fs::read_to_string(&path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        let mut value = serde_json::from_str::<Value>(&original)
+            .map_err(|error| Errors::Json(error.to_string()))?;
+        if !value["profiles"].is_object() {
+            value["profiles"] = json!({});
+        }
+        if let Some(profiles) = value["profiles"].as_object_mut() {
+            edit(profiles);
+        }
+        let contents = serde_json::to_string_pretty(&value)
+            .map_err(|error| Errors::Json(error.to_string()))?;
+
+        if fs::read_to_string(&path)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?
+            != original
+        {
+            continue;
+        }
+        backup(&path, &original).await?;
+        return write_atomically(&path, &contents).await;
+    }
+    Err(Errors::ProfilesChanged)
+}
+
+async fn backup(path: &Path, contents: &str) -> Result<(), Errors> {
+    let backups_path = paths::BACKUPS.clone()?;
+    fs::create_dir_all(&backups_path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    fs::write(
+        backups_path.join(format!("{}-{:020}.json", stem(path), timestamp)),
+        contents,
+    )
+    .await
+    .map_err(|error| Errors::Io(error.kind()))?;
+
+    let backups = backups(path).await?;
+    for old in &backups[..backups.len().saturating_sub(KEPT_BACKUPS)] {
+        fs::remove_file(old)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+    }
+    Ok(())
+}
+
+async fn backups(path: &Path) -> Result<Vec<PathBuf>, Errors> {
+    let backups_path = paths::BACKUPS.clone()?;
+    let mut entries = match fs::read_dir(backups_path).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(Errors::Io(error.kind())),
+    };
+    let prefix = format!("{}-", stem(path));
+    let mut backups = vec![];
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?
+    {
+        if entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with(&prefix))
+        {
+            backups.push(entry.path());
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+async fn write_atomically(path: &Path, contents: &str) -> Result<(), Errors> {
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    fs::rename(&temp_path, path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+fn stem(path: &Path) -> &str {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("launcher_profiles")
+}
+
+fn write_profile(profiles: &mut Map<String, Value>, instance: &Instance) {
+    fill_profile(
+        profiles.entry(key(instance)).or_insert_with(|| json!({})),
+        instance,
+    );
+}
+
+fn fill_profile(profile: &mut Value, instance: &Instance) {
+    if !profile.is_object() {
+        *profile = json!({});
+    }
+    profile["name"] = json!(instance.name());
+    profile["type"] = json!("custom");
+    profile["icon"] = json!(instance.icon().unwrap_or(DEFAULT_ICON));
+    profile["lastVersionId"] = json!(instance.last_version_id());
+    profile["gameDir"] = json!(instance.path());
+    match instance.java_path() {
+        Some(java_path) => profile["javaDir"] = json!(java_path),
+        None => {
+            if let Some(profile) = profile.as_object_mut() {
+                profile.remove("javaDir");
+            }
+        }
+    }
+}
+
+fn key(instance: &Instance) -> String {
+    format!("aethon-{}", instance.id().simple())
+}
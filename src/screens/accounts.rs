@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use iced::widget::image::Handle;
+use iced::widget::{button, column, horizontal_space, image, row, text, text_input, Column};
+use iced::{Alignment, Command, Element, Length};
+use uuid::Uuid;
+
+use crate::account::{Account, HEAD_SIZE};
+use crate::api::microsoft;
+use crate::screens::error::Error;
+use crate::screens::login::Login;
+use crate::screens::main::Main;
+use crate::screens::{centering_container, login, Messages, Screen, Screens};
+use crate::{account, Errors, CONFIG};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Accounts {
+    main: Main,
+    heads: HashMap<Uuid, Handle>,
+    offline_name: String,
+    renaming: Option<(Uuid, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    HeadLoaded(Uuid, Option<Vec<u8>>),
+    SignIn,
+    OfflineNameChanged(String),
+    AddOffline,
+    SetDefault(Uuid),
+    StartRename(Uuid),
+    NameChanged(String),
+    Rename,
+    Remove(Uuid),
+    Updated(Result<Vec<Account>, Errors>),
+    Back,
+}
+
+impl Accounts {
+    pub(crate) fn new(main: Main) -> Self {
+        Accounts {
+            main,
+            heads: HashMap::new(),
+            offline_name: String::new(),
+            renaming: None,
+        }
+    }
+
+    pub(crate) fn load_heads(&self) -> Command<Messages> {
+        Command::batch(self.main.accounts().iter().map(|account| {
+            let id = account.id;
+            Command::perform(account::head(account.clone()), move |head| {
+                Messages::Accounts(Message::HeadLoaded(id, head))
+            })
+        }))
+    }
+
+    fn update_accounts(
+        &self,
+        future: impl std::future::Future<Output = Result<Vec<Account>, Errors>> + Send + 'static,
+    ) -> Command<Messages> {
+        Command::perform(future, |result| {
+            Messages::Accounts(Message::Updated(result))
+        })
+    }
+}
+
+impl Screen for Accounts {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::HeadLoaded(id, head) => {
+                if let Some(pixels) = head {
+                    self.heads
+                        .insert(id, Handle::from_pixels(HEAD_SIZE, HEAD_SIZE, pixels));
+                }
+                (Command::none(), None)
+            }
+            Message::SignIn => (
+                Command::perform(microsoft::device_code(), |result| {
+                    Messages::Login(login::Message::CodeReceived(result))
+                }),
+                Some(Login::new(self.main.clone()).into()),
+            ),
+            Message::OfflineNameChanged(name) => {
+                self.offline_name = name;
+                (Command::none(), None)
+            }
+            Message::AddOffline => {
+                let name = self.offline_name.trim().to_string();
+                self.offline_name.clear();
+                (self.update_accounts(account::add_offline(name)), None)
+            }
+            Message::SetDefault(id) => {
+                self.main.set_default_account(Some(id));
+                (
+                    Command::perform(
+                        async move {
+                            let mut config = CONFIG.lock().await;
+                            config.default_account = Some(id);
+                            config.save().await
+                        },
+                        Messages::Save,
+                    ),
+                    None,
+                )
+            }
+            Message::StartRename(id) => {
+                self.renaming = self
+                    .main
+                    .accounts()
+                    .iter()
+                    .find(|account| account.id == id)
+                    .map(|account| (id, account.display_name().to_string()));
+                (Command::none(), None)
+            }
+            Message::NameChanged(name) => {
+                if let Some((_, label)) = &mut self.renaming {
+                    *label = name;
+                }
+                (Command::none(), None)
+            }
+            Message::Rename => match self.renaming.take() {
+                Some((id, label)) => (
+                    self.update_accounts(account::rename(id, label.trim().to_string())),
+                    None,
+                ),
+                None => (Command::none(), None),
+            },
+            Message::Remove(id) => (self.update_accounts(account::remove(id)), None),
+            Message::Updated(Ok(accounts)) => {
+                self.main.set_accounts(accounts);
+                (self.load_heads(), None)
+            }
+            Message::Updated(Err(error)) => (
+                Command::none(),
+                Some(Error::new(error, Box::new(self.clone().into())).into()),
+            ),
+            Message::Back => (Command::none(), Some(self.main.clone().into())),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let default_account = self
+            .main
+            .default_account()
+            .or_else(|| self.main.accounts().first().map(|account| account.id));
+        let accounts = Column::with_children(
+            self.main
+                .accounts()
+                .iter()
+                .map(|account| {
+                    let head: Element<'_, Messages> = match self.heads.get(&account.id) {
+                        Some(head) => image(head.clone()).into(),
+                        None => horizontal_space(HEAD_SIZE as f32).into(),
+                    };
+                    let is_default = default_account == Some(account.id);
+                    let name: Element<'_, Messages> = match &self.renaming {
+                        Some((id, label)) if *id == account.id => row![
+                            text_input("Account name", label)
+                                .on_input(|name| Messages::Accounts(Message::NameChanged(name)))
+                                .on_submit(Messages::Accounts(Message::Rename))
+                                .width(200),
+                            button("Save").on_press(Messages::Accounts(Message::Rename)),
+                        ]
+                        .spacing(10)
+                        .into(),
+                        _ => row![
+                            text(account.display_name()).width(200),
+                            button("Rename")
+                                .on_press(Messages::Accounts(Message::StartRename(account.id))),
+                        ]
+                        .spacing(10)
+                        .into(),
+                    };
+                    row![
+                        head,
+                        name,
+                        text(if account.is_microsoft() {
+                            "Microsoft"
+                        } else {
+                            "Offline"
+                        })
+                        .width(80),
+                        button(if is_default { "Default" } else { "Set default" }).on_press_maybe(
+                            if is_default {
+                                None
+                            } else {
+                                Some(Messages::Accounts(Message::SetDefault(account.id)))
+                            }
+                        ),
+                        button("Remove").on_press(Messages::Accounts(Message::Remove(account.id))),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(10);
+
+        centering_container(
+            column![
+                accounts,
+                row![
+                    button("Sign in with Microsoft").on_press(Messages::Accounts(Message::SignIn)),
+                    text_input("Offline username", &self.offline_name)
+                        .on_input(|name| Messages::Accounts(Message::OfflineNameChanged(name)))
+                        .width(200),
+                    button("Add offline").on_press_maybe(if self.offline_name.trim().is_empty() {
+                        None
+                    } else {
+                        Some(Messages::Accounts(Message::AddOffline))
+                    }),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                row![
+                    horizontal_space(Length::Fill),
+                    button("Back").on_press(Messages::Accounts(Message::Back)),
+                ],
+            ]
+            .spacing(20)
+            .width(Length::Shrink),
+        )
+        .into()
+    }
+}
+
+impl From<Accounts> for Screens {
+    fn from(value: Accounts) -> Self {
+        Screens::Accounts(value)
+    }
+}
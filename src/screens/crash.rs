@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use iced::widget::{button, column, container, row, scrollable, text, Column};
+use iced::{Alignment, Command, Element, Length};
+
+use crate::crash::Report;
+use crate::instance::Instance;
+use crate::screens::error::Error;
+use crate::screens::logs::Logs;
+use crate::screens::main::Main;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::{crash, Errors};
+
+const SHOWN_FRAMES: usize = 8;
+
+const START_SLACK: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub(crate) struct Crash {
+    main: Main,
+    instance: Instance,
+    report: Option<Report>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    Analyzed(Result<Report, Errors>),
+    Logs,
+    Back,
+}
+
+impl Crash {
+    pub(crate) fn open(main: Main, instance: Instance) -> (Command<Messages>, Option<Screens>) {
+        let since = instance
+            .last_run()
+            .map(|run| run.started_at() - START_SLACK);
+        (
+            Command::perform(crash::analyze(instance.clone(), since), |result| {
+                Messages::Crash(Message::Analyzed(result))
+            }),
+            Some(
+                Crash {
+                    main,
+                    instance,
+                    report: None,
+                }
+                .into(),
+            ),
+        )
+    }
+}
+
+impl Screen for Crash {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::Analyzed(Ok(report)) => {
+                self.report = Some(report);
+                (Command::none(), None)
+            }
+            Message::Analyzed(Err(error)) => (
+                Command::none(),
+                Some(Error::new(error, Box::new(self.main.clone().into())).into()),
+            ),
+            Message::Logs => (
+                Command::none(),
+                Some(Logs::new(self.main.clone(), self.instance.clone()).into()),
+            ),
+            Message::Back => (Command::none(), Some(self.main.clone().into())),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let buttons = row![
+            button("Logs").on_press(Messages::Crash(Message::Logs)),
+            button("Back").on_press(Messages::Crash(Message::Back)),
+        ]
+        .spacing(10);
+        let Some(report) = &self.report else {
+            return centering_container(
+                column![text("Analyzing crash..."), buttons]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .into();
+        };
+
+        let mut content = Column::new().spacing(10);
+        content = content.push(text(format!("{} crashed", self.instance.name())).size(24));
+        if let Some(description) = &report.description {
+            content = content.push(text(description));
+        }
+        if let Some(path) = &report.crash_report {
+            content = content.push(text(format!("Crash report: {}", path.display())).size(14));
+        }
+
+        if !report.findings.is_empty() {
+            content = content.push(text("Known issues").size(20));
+            for finding in &report.findings {
+                content = content.push(
+                    column![
+                        text(&finding.title),
+                        text(&finding.advice).size(14),
+                        text(&finding.line).size(12),
+                    ]
+                    .spacing(2),
+                );
+            }
+        }
+
+        if !report.suspects.is_empty() {
+            content = content.push(text("Suspected mods").size(20));
+            for suspect in &report.suspects {
+                content = content.push(text(format!("{}: {}", suspect.jar, suspect.reason)));
+            }
+        }
+
+        if !report.exceptions.is_empty() {
+            content = content.push(text("Exception").size(20));
+            for (i, exception) in report.exceptions.iter().enumerate() {
+                let header = if exception.message.is_empty() {
+                    exception.class.clone()
+                } else {
+                    format!("{}: {}", exception.class, exception.message)
+                };
+                let frames = exception
+                    .frames
+                    .iter()
+                    .take(SHOWN_FRAMES)
+                    .map(|frame| format!("    at {frame}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                content = content.push(
+                    column![
+                        text(if i == 0 {
+                            header
+                        } else {
+                            format!("Caused by: {header}")
+                        }),
+                        text(frames).size(12),
+                    ]
+                    .spacing(2),
+                );
+            }
+        }
+
+        if !report.mixin_failures.is_empty() {
+            content = content.push(text("Mixin failures").size(20));
+            content = content.push(text(report.mixin_failures.join("\n")).size(12));
+        }
+
+        if report.findings.is_empty() && report.suspects.is_empty() && report.exceptions.is_empty()
+        {
+            content = content.push(text("No cause could be determined, check the logs."));
+        }
+
+        centering_container(
+            column![
+                container(scrollable(content.width(Length::Fill))).height(Length::Fill),
+                buttons,
+            ]
+            .spacing(10),
+        )
+        .into()
+    }
+}
+
+impl From<Crash> for Screens {
+    fn from(value: Crash) -> Self {
+        Screens::Crash(value)
+    }
+}
@@ -0,0 +1,230 @@
+use iced::widget::{
+    button, checkbox, column, horizontal_space, pick_list, row, text, text_input, Row,
+};
+use iced::{Alignment, Command, Element, Length};
+
+use crate::api::mojang;
+use crate::api::mojang::{VersionManifest, VersionType};
+use crate::instance;
+use crate::instance::{Instance, Loader};
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::Errors;
+
+#[derive(Debug, Clone)]
+pub(crate) struct CreateInstance {
+    main: Main,
+    name: String,
+    manifest: Option<VersionManifest>,
+    version_types: Vec<VersionType>,
+    minecraft_version: String,
+    loader: Loader,
+    loader_version: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    NameChanged(String),
+    ManifestLoaded(Result<VersionManifest, Errors>),
+    VersionTypeToggled(VersionType, bool),
+    MinecraftVersionChanged(String),
+    LoaderSelected(Loader),
+    LoaderVersionChanged(String),
+    Create,
+    Created(Box<Result<Instance, Errors>>),
+    Cancel,
+}
+
+impl CreateInstance {
+    pub(crate) fn new(main: Main) -> Self {
+        CreateInstance {
+            main,
+            name: String::new(),
+            manifest: None,
+            version_types: vec![VersionType::Release],
+            minecraft_version: String::from("latest-release"),
+            loader: Loader::Vanilla,
+            loader_version: String::new(),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        instance::is_valid_name(&self.name)
+            && !self.minecraft_version.trim().is_empty()
+            && (self.loader == Loader::Vanilla || !self.loader_version.trim().is_empty())
+    }
+}
+
+impl Screen for CreateInstance {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::NameChanged(name) => {
+                self.name = name;
+                (Command::none(), None)
+            }
+            Message::ManifestLoaded(result) => match result {
+                Ok(manifest) => {
+                    if manifest.find(&self.minecraft_version).is_none()
+                        || self.minecraft_version == "latest-release"
+                    {
+                        self.minecraft_version = manifest.latest.release.clone();
+                    }
+                    self.manifest = Some(manifest);
+                    (Command::none(), None)
+                }
+                Err(error) => (
+                    Command::none(),
+                    Some(Error::new(error, Box::new(self.clone().into())).into()),
+                ),
+            },
+            Message::VersionTypeToggled(version_type, enabled) => {
+                self.version_types.retain(|other| *other != version_type);
+                if enabled {
+                    self.version_types.push(version_type);
+                }
+                (Command::none(), None)
+            }
+            Message::MinecraftVersionChanged(minecraft_version) => {
+                self.minecraft_version = minecraft_version;
+                (Command::none(), None)
+            }
+            Message::LoaderSelected(loader) => {
+                self.loader = loader;
+                (Command::none(), None)
+            }
+            Message::LoaderVersionChanged(loader_version) => {
+                self.loader_version = loader_version;
+                (Command::none(), None)
+            }
+            Message::Create => {
+                let name = self.name.trim().to_string();
+                let minecraft_version = self.minecraft_version.trim().to_string();
+                let loader = self.loader;
+                let loader_version = self.loader_version.trim().to_string();
+                (
+                    Command::perform(
+                        async move {
+                            let manifest = mojang::manifest().await?;
+                            let entry = manifest
+                                .find(&minecraft_version)
+                                .ok_or_else(|| Errors::UnknownVersion(minecraft_version.clone()))?;
+                            mojang::version(entry).await?;
+                            Instance::new(
+                                &name,
+                                &minecraft_version,
+                                loader,
+                                Some(loader_version.as_str()),
+                            )
+                            .await
+                        },
+                        |result| Messages::CreateInstance(Message::Created(Box::new(result))),
+                    ),
+                    None,
+                )
+            }
+            Message::Created(result) => (
+                Command::none(),
+                match *result {
+                    Ok(instance) => {
+                        let mut main = self.main.clone();
+                        main.add_instance(instance);
+                        Some(main.into())
+                    }
+                    Err(error) => Some(Error::new(error, Box::new(self.clone().into())).into()),
+                },
+            ),
+            Message::Cancel => (Command::none(), Some(self.main.clone().into())),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let loader_version = {
+            let mut loader_version = text_input("Loader version", &self.loader_version);
+            if self.loader != Loader::Vanilla {
+                loader_version = loader_version.on_input(|version| {
+                    Messages::CreateInstance(Message::LoaderVersionChanged(version))
+                });
+            }
+            loader_version
+        };
+
+        let minecraft_version: Element<'_, Messages> = match &self.manifest {
+            Some(manifest) => column![
+                Row::with_children(
+                    VersionType::ALL
+                        .into_iter()
+                        .map(|version_type| {
+                            checkbox(
+                                version_type.name(),
+                                self.version_types.contains(&version_type),
+                                move |enabled| {
+                                    Messages::CreateInstance(Message::VersionTypeToggled(
+                                        version_type,
+                                        enabled,
+                                    ))
+                                },
+                            )
+                            .into()
+                        })
+                        .collect(),
+                )
+                .spacing(10),
+                pick_list(
+                    manifest
+                        .filter(&self.version_types)
+                        .map(|version| version.id.clone())
+                        .collect::<Vec<_>>(),
+                    Some(self.minecraft_version.clone()),
+                    |version| Messages::CreateInstance(Message::MinecraftVersionChanged(version)),
+                )
+                .width(Length::Fill),
+            ]
+            .spacing(10)
+            .into(),
+            None => text_input("Minecraft version", &self.minecraft_version)
+                .on_input(|version| {
+                    Messages::CreateInstance(Message::MinecraftVersionChanged(version))
+                })
+                .into(),
+        };
+
+        centering_container(
+            column![
+                text_input("Instance name", &self.name)
+                    .on_input(|name| Messages::CreateInstance(Message::NameChanged(name))),
+                minecraft_version,
+                row![
+                    text("Loader"),
+                    pick_list(&Loader::ALL[..], Some(self.loader), |loader| {
+                        Messages::CreateInstance(Message::LoaderSelected(loader))
+                    }),
+                    loader_version,
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                row![
+                    horizontal_space(Length::Fill),
+                    button("Cancel").on_press(Messages::CreateInstance(Message::Cancel)),
+                    button("Create").on_press_maybe(if self.is_valid() {
+                        Some(Messages::CreateInstance(Message::Create))
+                    } else {
+                        None
+                    }),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10)
+            .width(400),
+        )
+        .into()
+    }
+}
+
+impl From<CreateInstance> for Screens {
+    fn from(value: CreateInstance) -> Self {
+        Screens::CreateInstance(value)
+    }
+}
@@ -0,0 +1,459 @@
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, row, scrollable, text, text_input,
+    Column,
+};
+use iced::{theme, Alignment, Command, Element, Length};
+
+use crate::api::curseforge;
+use crate::api::curseforge::{File, Mod, SearchResults};
+use crate::instance::Instance;
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::mods::Mods;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::{paths, Errors, CONFIG};
+
+#[derive(Debug, Clone)]
+pub(crate) struct CurseForge {
+    main: Main,
+    instance: Instance,
+    query: String,
+    game_version_filter: bool,
+    loader_filter: bool,
+    results: Option<SearchResults>,
+    project: Option<Mod>,
+    files: Option<Vec<File>>,
+    api_key: Option<String>,
+    status: Option<String>,
+    busy: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    QueryChanged(String),
+    Search,
+    Searched(Result<SearchResults, Errors>),
+    Page(usize),
+    GameVersionToggled(bool),
+    LoaderToggled(bool),
+    Open(usize),
+    FilesLoaded(u64, Result<Vec<File>, Errors>),
+    Install(usize),
+    Installed(Box<(Instance, Result<Vec<String>, Errors>)>),
+    OpenWebsite(String),
+    Link,
+    Linked(Box<Result<(Instance, usize), Errors>>),
+    ApiKeyChanged(String),
+    SaveApiKey,
+    Done(Result<(), Errors>),
+    Back,
+}
+
+impl CurseForge {
+    pub(crate) fn open(main: Main, instance: Instance) -> (Command<Messages>, Option<Screens>) {
+        let curseforge = CurseForge {
+            main,
+            instance,
+            query: String::new(),
+            game_version_filter: true,
+            loader_filter: true,
+            results: None,
+            project: None,
+            files: None,
+            api_key: None,
+            status: None,
+            busy: false,
+        };
+        (curseforge.search(0), Some(curseforge.into()))
+    }
+
+    fn search(&self, offset: usize) -> Command<Messages> {
+        Command::perform(
+            curseforge::search(
+                self.query.clone(),
+                self.game_version_filter
+                    .then(|| self.instance.minecraft_version().to_string()),
+                self.loader_filter.then(|| self.instance.loader()),
+                offset,
+            ),
+            |result| Messages::CurseForge(Message::Searched(result)),
+        )
+    }
+
+    fn error(&mut self, error: Errors) -> (Command<Messages>, Option<Screens>) {
+        self.busy = false;
+        self.status = None;
+        if let Errors::MissingApiKey = error {
+            self.api_key = Some(String::new());
+            return (Command::none(), None);
+        }
+        (
+            Command::none(),
+            Some(Error::new(error, Box::new(self.clone().into())).into()),
+        )
+    }
+
+    fn details<'a>(&'a self, project: &'a Mod) -> Element<'a, Messages> {
+        let mut details = column![
+            text(&project.name).size(24),
+            text(&project.summary),
+            text(format!(
+                "by {}, {} downloads",
+                authors(project),
+                project.download_count as u64
+            ))
+            .size(14),
+        ]
+        .spacing(5);
+        let website = project.links.website_url.clone();
+        if !project.allows_distribution() {
+            details = details.push(text(
+                "The author does not allow downloads through other launchers, get it from the website instead.",
+            ));
+        }
+        if let Some(url) = website {
+            details = details.push(
+                button("Open website").on_press(Messages::CurseForge(Message::OpenWebsite(url))),
+            );
+        }
+        let Some(files) = &self.files else {
+            return details.push(text("Loading files...")).into();
+        };
+        if files.is_empty() {
+            return details
+                .push(text(format!(
+                    "No file supports Minecraft {} with {}",
+                    self.instance.minecraft_version(),
+                    self.instance.loader()
+                )))
+                .into();
+        }
+        for (i, file) in files.iter().enumerate() {
+            let installable =
+                !self.busy && project.allows_distribution() && file.download_url.is_some();
+            details = details.push(
+                row![
+                    column![
+                        text(&file.display_name),
+                        text(format!(
+                            "{}, {}, {}, {}",
+                            file.file_name,
+                            file.release_type(),
+                            file.game_versions.join(", "),
+                            file.file_date.get(..10).unwrap_or_default()
+                        ))
+                        .size(12),
+                    ]
+                    .spacing(2)
+                    .width(Length::Fill),
+                    button("Install").on_press_maybe(
+                        installable.then_some(Messages::CurseForge(Message::Install(i)))
+                    ),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            );
+        }
+        details.into()
+    }
+}
+
+impl Screen for CurseForge {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::QueryChanged(query) => {
+                self.query = query;
+                (Command::none(), None)
+            }
+            Message::Search => (self.search(0), None),
+            Message::Searched(Ok(results)) => {
+                self.api_key = None;
+                self.results = Some(results);
+                (Command::none(), None)
+            }
+            Message::Page(offset) => (self.search(offset), None),
+            Message::GameVersionToggled(enabled) => {
+                self.game_version_filter = enabled;
+                (self.search(0), None)
+            }
+            Message::LoaderToggled(enabled) => {
+                self.loader_filter = enabled;
+                (self.search(0), None)
+            }
+            Message::Open(i) => {
+                let Some(project) = self
+                    .results
+                    .as_ref()
+                    .and_then(|results| results.data.get(i))
+                else {
+                    return (Command::none(), None);
+                };
+                let mod_id = project.id;
+                self.project = Some(project.clone());
+                self.files = None;
+                (
+                    Command::perform(
+                        curseforge::files(
+                            mod_id,
+                            self.instance.minecraft_version().to_string(),
+                            self.instance.loader(),
+                        ),
+                        move |result| Messages::CurseForge(Message::FilesLoaded(mod_id, result)),
+                    ),
+                    None,
+                )
+            }
+            Message::FilesLoaded(mod_id, Ok(files)) => {
+                if self
+                    .project
+                    .as_ref()
+                    .is_some_and(|project| project.id == mod_id)
+                {
+                    self.files = Some(files);
+                }
+                (Command::none(), None)
+            }
+            Message::Install(i) => {
+                let (Some(project), Some(file)) = (
+                    &self.project,
+                    self.files.as_ref().and_then(|files| files.get(i)),
+                ) else {
+                    return (Command::none(), None);
+                };
+                self.busy = true;
+                self.status = Some(format!("Installing {}...", file.display_name));
+                (
+                    Command::perform(
+                        curseforge::install(self.instance.clone(), project.clone(), file.clone()),
+                        |result| Messages::CurseForge(Message::Installed(Box::new(result))),
+                    ),
+                    None,
+                )
+            }
+            Message::Installed(result) => {
+                let (instance, result) = *result;
+                self.main.update_instance(instance.clone());
+                self.instance = instance;
+                match result {
+                    Ok(installed) => {
+                        self.busy = false;
+                        self.status = Some(format!("Installed {}", installed.join(", ")));
+                        (Command::none(), None)
+                    }
+                    Err(error) => self.error(error),
+                }
+            }
+            Message::OpenWebsite(url) => (
+                Command::perform(async move { paths::open(url) }, |result| {
+                    Messages::CurseForge(Message::Done(result))
+                }),
+                None,
+            ),
+            Message::Link => {
+                self.busy = true;
+                self.status = Some(String::from("Identifying local jars..."));
+                (
+                    Command::perform(curseforge::link(self.instance.clone()), |result| {
+                        Messages::CurseForge(Message::Linked(Box::new(result)))
+                    }),
+                    None,
+                )
+            }
+            Message::Linked(result) => match *result {
+                Ok((instance, linked)) => {
+                    self.busy = false;
+                    self.status = Some(format!("Linked {linked} jars to CurseForge"));
+                    self.main.update_instance(instance.clone());
+                    self.instance = instance;
+                    (Command::none(), None)
+                }
+                Err(error) => self.error(error),
+            },
+            Message::ApiKeyChanged(api_key) => {
+                self.api_key = Some(api_key);
+                (Command::none(), None)
+            }
+            Message::SaveApiKey => {
+                let api_key = self.api_key.clone().unwrap_or_default().trim().to_string();
+                let query = self.query.clone();
+                let game_version = self
+                    .game_version_filter
+                    .then(|| self.instance.minecraft_version().to_string());
+                let loader = self.loader_filter.then(|| self.instance.loader());
+                (
+                    Command::perform(
+                        async move {
+                            {
+                                let mut config = CONFIG.lock().await;
+                                config.curseforge_api_key = Some(api_key);
+                                config.save().await?;
+                            }
+                            curseforge::search(query, game_version, loader, 0).await
+                        },
+                        |result| Messages::CurseForge(Message::Searched(result)),
+                    ),
+                    None,
+                )
+            }
+            Message::Back => Mods::open(self.main.clone(), self.instance.clone()),
+            Message::Done(Ok(())) => (Command::none(), None),
+            Message::Searched(Err(error))
+            | Message::FilesLoaded(_, Err(error))
+            | Message::Done(Err(error)) => self.error(error),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let title = text(format!("CurseForge: {}", self.instance.name())).size(24);
+        let back = button("Back").on_press(Messages::CurseForge(Message::Back));
+        if let Some(api_key) = &self.api_key {
+            return centering_container(
+                column![
+                    title,
+                    text("CurseForge requires an API key, which you can request at console.curseforge.com."),
+                    text_input("API key", api_key)
+                        .on_input(|api_key| Messages::CurseForge(Message::ApiKeyChanged(api_key)))
+                        .on_submit(Messages::CurseForge(Message::SaveApiKey)),
+                    row![
+                        back,
+                        button("Save").on_press_maybe(
+                            (!api_key.trim().is_empty())
+                                .then_some(Messages::CurseForge(Message::SaveApiKey))
+                        ),
+                    ]
+                    .spacing(10),
+                ]
+                .spacing(10)
+                .width(500),
+            )
+            .into();
+        }
+
+        let search = row![
+            text_input("Search CurseForge", &self.query)
+                .on_input(|query| Messages::CurseForge(Message::QueryChanged(query)))
+                .on_submit(Messages::CurseForge(Message::Search)),
+            checkbox(
+                format!("Minecraft {}", self.instance.minecraft_version()),
+                self.game_version_filter,
+                |enabled| Messages::CurseForge(Message::GameVersionToggled(enabled)),
+            ),
+            checkbox(
+                self.instance.loader().to_string(),
+                self.loader_filter,
+                |enabled| Messages::CurseForge(Message::LoaderToggled(enabled)),
+            ),
+            button("Search").on_press(Messages::CurseForge(Message::Search)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let mut results = Column::new().spacing(2);
+        let mut pages = row![].spacing(10).align_items(Alignment::Center);
+        match &self.results {
+            Some(search) => {
+                for (i, project) in search.data.iter().enumerate() {
+                    let is_selected = self
+                        .project
+                        .as_ref()
+                        .is_some_and(|selected| selected.id == project.id);
+                    results = results.push(
+                        button(
+                            column![
+                                text(&project.name),
+                                text(format!(
+                                    "by {}, {} downloads",
+                                    authors(project),
+                                    project.download_count as u64
+                                ))
+                                .size(12),
+                                text(&project.summary).size(14),
+                            ]
+                            .spacing(2),
+                        )
+                        .style(if is_selected {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        })
+                        .width(Length::Fill)
+                        .on_press(Messages::CurseForge(Message::Open(i))),
+                    );
+                }
+                let offset = search.pagination.index;
+                let end = offset + search.data.len();
+                pages = pages
+                    .push(button("Previous").on_press_maybe((offset > 0).then(|| {
+                        Messages::CurseForge(Message::Page(
+                            offset.saturating_sub(curseforge::PAGE_SIZE),
+                        ))
+                    })))
+                    .push(text(format!(
+                        "{}-{end} of {}",
+                        (offset + 1).min(end),
+                        search.pagination.total_count
+                    )))
+                    .push(
+                        button("Next").on_press_maybe(
+                            (end < search.pagination.total_count)
+                                .then_some(Messages::CurseForge(Message::Page(end))),
+                        ),
+                    );
+            }
+            None => results = results.push(text("Searching...")),
+        }
+
+        let details: Element<'_, Messages> = match &self.project {
+            Some(project) => self.details(project),
+            None => text("Select a project to see its files").into(),
+        };
+
+        let mut footer = row![
+            button("Link local jars")
+                .on_press_maybe((!self.busy).then_some(Messages::CurseForge(Message::Link))),
+            horizontal_space(Length::Fill),
+            pages,
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+        if let Some(status) = &self.status {
+            footer = footer.push(text(status));
+        }
+        footer = footer.push(back);
+
+        centering_container(
+            column![
+                title,
+                search,
+                row![
+                    scrollable(results).width(Length::FillPortion(1)),
+                    container(scrollable(details))
+                        .width(Length::FillPortion(1))
+                        .padding(10),
+                ]
+                .spacing(10)
+                .height(Length::Fill),
+                footer,
+            ]
+            .spacing(10),
+        )
+        .into()
+    }
+}
+
+impl From<CurseForge> for Screens {
+    fn from(value: CurseForge) -> Self {
+        Screens::CurseForge(value)
+    }
+}
+
+fn authors(project: &Mod) -> String {
+    project
+        .authors
+        .iter()
+        .map(|author| author.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
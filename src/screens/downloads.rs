@@ -0,0 +1,167 @@
+use iced::widget::{button, column, container, progress_bar, row, scrollable, text, Column};
+use iced::{theme, Alignment, Command, Element, Length, Subscription};
+
+use crate::download;
+use crate::download::{Snapshot, State};
+use crate::screens::error::message;
+use crate::screens::Messages;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Downloads {
+    snapshot: Snapshot,
+    expanded: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    Progress(Snapshot),
+    Toggle,
+    Cancel(u64),
+    Retry(u64),
+    Retried,
+    Dismiss(u64),
+}
+
+impl Downloads {
+    pub(crate) fn update(&mut self, message: Message) -> Command<Messages> {
+        match message {
+            Message::Progress(snapshot) => {
+                self.snapshot = snapshot;
+                if self.snapshot.downloads.is_empty() {
+                    self.expanded = false;
+                }
+            }
+            Message::Toggle => self.expanded = !self.expanded,
+            Message::Cancel(id) => download::cancel(id),
+            Message::Retry(id) => {
+                return Command::perform(download::retry(id), |_| {
+                    Messages::Downloads(Message::Retried)
+                });
+            }
+            Message::Retried => {}
+            Message::Dismiss(id) => download::dismiss(id),
+        }
+        Command::none()
+    }
+
+    pub(crate) fn view(&self) -> Option<Element<'_, Messages>> {
+        if self.snapshot.downloads.is_empty() {
+            return None;
+        }
+
+        let queued = self
+            .snapshot
+            .downloads
+            .iter()
+            .filter(|progress| matches!(progress.state, State::Queued))
+            .count();
+        let failed = self
+            .snapshot
+            .downloads
+            .iter()
+            .filter(|progress| !progress.state.is_active())
+            .count();
+        let summary = if self.snapshot.is_active() {
+            format!(
+                "Downloading {} of {} files ({} / {})",
+                self.snapshot.finished,
+                self.snapshot.finished + self.snapshot.downloads.len() - failed,
+                size(self.snapshot.downloaded),
+                size(self.snapshot.total)
+            )
+        } else {
+            format!("{failed} download(s) did not finish")
+        };
+        let bar = row![
+            text(summary).size(14),
+            progress_bar(
+                0.0..=self.snapshot.total.max(1) as f32,
+                self.snapshot.downloaded as f32
+            )
+            .height(8),
+            button(if self.expanded { "Hide" } else { "Show" })
+                .style(theme::Button::Secondary)
+                .on_press(Messages::Downloads(Message::Toggle)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+        if !self.expanded {
+            return Some(container(bar).padding(10).into());
+        }
+
+        let mut list = Column::new().spacing(5);
+        for progress in &self.snapshot.downloads {
+            let (status, actions) = match &progress.state {
+                State::Queued => continue,
+                State::Running => (
+                    format!(
+                        "{} / {}",
+                        size(progress.downloaded),
+                        progress
+                            .total
+                            .map(size)
+                            .unwrap_or_else(|| String::from("?"))
+                    ),
+                    row![button("Cancel")
+                        .on_press(Messages::Downloads(Message::Cancel(progress.id)))],
+                ),
+                State::Retrying(attempt) => (
+                    format!("Retrying after attempt {attempt}"),
+                    row![button("Cancel")
+                        .on_press(Messages::Downloads(Message::Cancel(progress.id)))],
+                ),
+                State::Failed(error) => (
+                    message(error),
+                    row![
+                        button("Retry").on_press(Messages::Downloads(Message::Retry(progress.id))),
+                        button("Dismiss")
+                            .style(theme::Button::Secondary)
+                            .on_press(Messages::Downloads(Message::Dismiss(progress.id))),
+                    ]
+                    .spacing(5),
+                ),
+                State::Cancelled => (
+                    String::from("Cancelled"),
+                    row![
+                        button("Retry").on_press(Messages::Downloads(Message::Retry(progress.id))),
+                        button("Dismiss")
+                            .style(theme::Button::Secondary)
+                            .on_press(Messages::Downloads(Message::Dismiss(progress.id))),
+                    ]
+                    .spacing(5),
+                ),
+            };
+            list = list.push(
+                row![
+                    column![text(&progress.name), text(status).size(12)].width(Length::Fill),
+                    progress_bar(
+                        0.0..=progress.total.unwrap_or(progress.downloaded).max(1) as f32,
+                        progress.downloaded as f32
+                    )
+                    .width(Length::Fixed(150.0))
+                    .height(8),
+                    actions,
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            );
+        }
+        if queued > 0 {
+            list = list.push(text(format!("{queued} queued")).size(12));
+        }
+
+        Some(
+            container(column![bar, scrollable(list).height(Length::Fixed(200.0)),].spacing(10))
+                .padding(10)
+                .into(),
+        )
+    }
+
+    pub(crate) fn subscription(&self) -> Subscription<Messages> {
+        download::progress().map(|snapshot| Messages::Downloads(Message::Progress(snapshot)))
+    }
+}
+
+fn size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
@@ -1,4 +1,4 @@
-use iced::widget::{button, column, container, horizontal_space, row, text};
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text};
 use iced::{Command, Element, Length};
 
 use crate::screens::{centering_container, Messages, Screen, Screens};
@@ -22,6 +22,14 @@ impl Error {
             previous_screen,
         }
     }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        matches!(self.error, Errors::Cancelled)
+    }
+
+    pub(crate) fn previous_screen(self) -> Screens {
+        *self.previous_screen
+    }
 }
 
 impl Screen for Error {
@@ -34,50 +42,74 @@ impl Screen for Error {
     }
 
     fn view(&self) -> Element<'_, Messages> {
-        match &self.error {
-            Errors::Io(error_kind) => centering_container(
-                column![
-                    container(text(error_kind)).width(Length::Fill).center_x(),
-                    row![
-                        horizontal_space(Length::FillPortion(2)),
-                        button(container("Continue").width(Length::Fill).center_x())
-                            .width(Length::Fill)
-                            .on_press(Messages::Error(Message::Continue)),
-                        horizontal_space(Length::FillPortion(2)),
-                    ]
-                ]
-                .spacing(10),
-            )
-            .into(),
-            Errors::Json(error) => centering_container(
-                column![
-                    container(text(error)).width(Length::Fill).center_x(),
-                    row![
-                        horizontal_space(Length::FillPortion(2)),
-                        button(container("Continue").width(Length::Fill).center_x())
-                            .width(Length::Fill)
-                            .on_press(Messages::Error(Message::Continue)),
-                        horizontal_space(Length::FillPortion(2)),
-                    ]
+        centering_container(
+            column![
+                scrollable(
+                    container(text(message(&self.error)))
+                        .width(Length::Fill)
+                        .center_x()
+                ),
+                row![
+                    horizontal_space(Length::FillPortion(2)),
+                    button(container("Continue").width(Length::Fill).center_x())
+                        .width(Length::Fill)
+                        .on_press(Messages::Error(Message::Continue)),
+                    horizontal_space(Length::FillPortion(2)),
                 ]
-                .spacing(10),
-            )
-            .into(),
-            Errors::NoParent => centering_container(
-                column![
-                    container(text("No parent")).width(Length::Fill).center_x(),
-                    row![
-                        horizontal_space(Length::FillPortion(2)),
-                        button(container("Continue").width(Length::Fill).center_x())
-                            .width(Length::Fill)
-                            .on_press(Messages::Error(Message::Continue)),
-                        horizontal_space(Length::FillPortion(2)),
-                    ]
-                ]
-                .spacing(10),
-            )
-            .into(),
-        }
+            ]
+            .spacing(10),
+        )
+        .into()
+    }
+}
+
+pub(crate) fn message(error: &Errors) -> String {
+    match error {
+            Errors::Io(error_kind) => error_kind.to_string(),
+            Errors::Json(error)
+            | Errors::Http(error)
+            | Errors::Archive(error)
+            | Errors::Toml(error) => error.clone(),
+            Errors::NoParent => String::from("No parent"),
+            Errors::UnknownVersion(version) => format!("Unknown Minecraft version {version}"),
+            Errors::MissingLoaderVersion => String::from("The instance has no loader version set."),
+            Errors::MissingLibrary(library) => format!("Missing library {library}"),
+            Errors::Installer(log) => format!("The loader installer failed:\n\n{log}"),
+            Errors::ProfilesChanged => String::from(
+                "The launcher profiles kept changing while being edited, please close the launcher and try again.",
+            ),
+            Errors::NoBackup => String::from("There is no launcher profiles backup to restore."),
+            Errors::MissingClientId => String::from(
+                "No Microsoft client id is configured, set microsoft_client_id in config.json.",
+            ),
+            Errors::LoginDeclined => String::from("The sign-in request was declined."),
+            Errors::LoginExpired => {
+                String::from("The sign-in has expired, please sign in again.")
+            }
+            Errors::NoXboxAccount => String::from(
+                "This Microsoft account has no Xbox profile, please create one at xbox.com first.",
+            ),
+            Errors::XboxRestricted(code) => format!(
+                "Xbox Live refused the sign-in for this account ({code}), it may be a child account or unavailable in your region."
+            ),
+            Errors::NoMinecraftLicense => {
+                String::from("This account does not own Minecraft: Java Edition.")
+            }
+            Errors::Login(error) => format!("Sign-in failed: {error}"),
+            Errors::Encryption => {
+                String::from("The stored accounts could not be decrypted, please sign in again.")
+            }
+            Errors::NoCompatibleVersion(project) => {
+                format!("No version of {project} is compatible with this instance.")
+            }
+            Errors::MissingApiKey => String::from("No CurseForge API key is configured."),
+            Errors::DistributionNotAllowed(name) => {
+                format!("{name} does not allow third-party downloads, please download it from its website.")
+            }
+            Errors::ChecksumMismatch(file) => format!("{file} did not match its checksum."),
+            Errors::Cancelled => String::from("The download was cancelled."),
+        Errors::InvalidModpack(reason) => format!("The modpack could not be read: {reason}."),
+        Errors::InvalidFileName(name) => format!("{name} is not a valid file name."),
     }
 }
 
@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use iced::widget::{button, checkbox, column, horizontal_space, row, scrollable, text, Column};
+use iced::{Alignment, Command, Element, Length};
+
+use crate::instance::Instance;
+use crate::modpack::Modpack;
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::{modpack, Errors};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Import {
+    main: Main,
+    modpack: Modpack,
+    optional: HashSet<String>,
+    importing: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    Toggle(String, bool),
+    Import,
+    Imported(Box<Result<Instance, Errors>>),
+    Cancel,
+}
+
+impl Import {
+    pub(crate) fn new(main: Main, modpack: Modpack) -> Self {
+        Import {
+            main,
+            modpack,
+            optional: HashSet::new(),
+            importing: false,
+        }
+    }
+}
+
+impl Screen for Import {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::Toggle(path, selected) => {
+                if selected {
+                    self.optional.insert(path);
+                } else {
+                    self.optional.remove(&path);
+                }
+                (Command::none(), None)
+            }
+            Message::Import => {
+                self.importing = true;
+                (
+                    Command::perform(
+                        modpack::import(self.modpack.clone(), self.optional.clone()),
+                        |result| Messages::Import(Message::Imported(Box::new(result))),
+                    ),
+                    None,
+                )
+            }
+            Message::Imported(result) => match *result {
+                Ok(instance) => {
+                    let mut main = self.main.clone();
+                    main.add_instance(instance);
+                    (Command::none(), Some(main.into()))
+                }
+                Err(error) => {
+                    self.importing = false;
+                    (
+                        Command::none(),
+                        Some(Error::new(error, Box::new(self.clone().into())).into()),
+                    )
+                }
+            },
+            Message::Cancel => (Command::none(), Some(self.main.clone().into())),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let index = &self.modpack.index;
+        let (loader, loader_version) = index.loader();
+        let mut details = column![
+            text(format!("{} {}", index.name, index.version_id)).size(24),
+            text(format!(
+                "Minecraft {}, {loader} {}",
+                index.minecraft_version().unwrap_or_default(),
+                loader_version.unwrap_or_default()
+            )),
+        ]
+        .spacing(5);
+        if let Some(summary) = &index.summary {
+            details = details.push(text(summary).size(14));
+        }
+
+        let mut optional = Column::new().spacing(5);
+        for file in index.files.iter().filter(|file| file.is_optional()) {
+            let path = file.path.clone();
+            optional = optional.push(checkbox(
+                &file.path,
+                self.optional.contains(&file.path),
+                move |selected| Messages::Import(Message::Toggle(path.clone(), selected)),
+            ));
+        }
+        if index.files.iter().any(|file| file.is_optional()) {
+            details = details.push(text("Optional files").size(20));
+        }
+
+        centering_container(
+            column![
+                details,
+                scrollable(optional).height(Length::Fill),
+                row![
+                    horizontal_space(Length::Fill),
+                    button("Cancel").on_press_maybe(
+                        (!self.importing).then_some(Messages::Import(Message::Cancel))
+                    ),
+                    button(if self.importing {
+                        "Importing..."
+                    } else {
+                        "Import"
+                    })
+                    .on_press_maybe((!self.importing).then_some(Messages::Import(Message::Import))),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            ]
+            .spacing(10)
+            .width(500),
+        )
+        .into()
+    }
+}
+
+impl From<Import> for Screens {
+    fn from(value: Import) -> Self {
+        Screens::Import(value)
+    }
+}
+
+pub(crate) async fn select_modpack() -> Option<Result<Modpack, Errors>> {
+    let file = rfd::AsyncFileDialog::new()
+        .set_title("Import Modpack")
+        .add_filter("Modrinth modpack", &["mrpack"])
+        .pick_file()
+        .await?;
+    Some(modpack::read(PathBuf::from(file.path())).await)
+}
@@ -0,0 +1,84 @@
+use iced::widget::{column, container, scrollable, text};
+use iced::{Command, Element, Length, Subscription};
+
+use crate::install;
+use crate::install::Progress;
+use crate::instance::Instance;
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Installing {
+    main: Main,
+    instance: Instance,
+    log: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    Progress(Progress),
+}
+
+impl Installing {
+    pub(crate) fn new(main: Main, instance: Instance) -> Self {
+        Installing {
+            main,
+            instance,
+            log: vec![],
+        }
+    }
+}
+
+impl Screen for Installing {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        let Message::Progress(progress) = message;
+        match progress {
+            Progress::Output(line) => {
+                self.log.push(line);
+                (Command::none(), None)
+            }
+            Progress::Finished(result) => match *result {
+                Ok(instance) => {
+                    let mut main = self.main.clone();
+                    let command = main.launch(instance);
+                    (command, Some(main.into()))
+                }
+                Err(error) => (
+                    Command::none(),
+                    Some(Error::new(error, Box::new(self.main.clone().into())).into()),
+                ),
+            },
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        centering_container(
+            column![
+                text(format!(
+                    "Installing {} {}",
+                    self.instance.loader(),
+                    self.instance.loader_version().unwrap_or_default()
+                )),
+                container(scrollable(text(self.log.join("\n")).size(14)).height(Length::Fill))
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            ]
+            .spacing(10),
+        )
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Messages> {
+        install::installer(self.instance.clone())
+            .map(|progress| Messages::Installing(Message::Progress(progress)))
+    }
+}
+
+impl From<Installing> for Screens {
+    fn from(value: Installing) -> Self {
+        Screens::Installing(value)
+    }
+}
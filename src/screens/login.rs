@@ -0,0 +1,99 @@
+use iced::widget::{button, column, row, text};
+use iced::{clipboard, Alignment, Command, Element, Subscription};
+
+use crate::account;
+use crate::account::Account;
+use crate::api::microsoft::DeviceCode;
+use crate::screens::accounts::Accounts;
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::Errors;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Login {
+    main: Main,
+    code: Option<DeviceCode>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    CodeReceived(Result<DeviceCode, Errors>),
+    Copy,
+    LoggedIn(Result<Vec<Account>, Errors>),
+    Cancel,
+}
+
+impl Login {
+    pub(crate) fn new(main: Main) -> Self {
+        Login { main, code: None }
+    }
+}
+
+impl Screen for Login {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::CodeReceived(Ok(code)) => {
+                self.code = Some(code);
+                (Command::none(), None)
+            }
+            Message::Copy => match &self.code {
+                Some(code) => (clipboard::write(code.user_code.clone()), None),
+                None => (Command::none(), None),
+            },
+            Message::LoggedIn(Ok(accounts)) => {
+                let mut main = self.main.clone();
+                main.set_accounts(accounts);
+                let accounts = Accounts::new(main);
+                (accounts.load_heads(), Some(accounts.into()))
+            }
+            Message::CodeReceived(Err(error)) | Message::LoggedIn(Err(error)) => (
+                Command::none(),
+                Some(Error::new(error, Box::new(Accounts::new(self.main.clone()).into())).into()),
+            ),
+            Message::Cancel => {
+                let accounts = Accounts::new(self.main.clone());
+                (accounts.load_heads(), Some(accounts.into()))
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let content = match &self.code {
+            Some(code) => column![
+                text(format!("Open {} and enter the code", code.verification_uri)),
+                row![
+                    text(&code.user_code).size(30),
+                    button("Copy").on_press(Messages::Login(Message::Copy)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                text("Waiting for sign-in..."),
+            ],
+            None => column![text("Requesting sign-in code...")],
+        };
+        centering_container(
+            content
+                .push(button("Cancel").on_press(Messages::Login(Message::Cancel)))
+                .spacing(10)
+                .align_items(Alignment::Center),
+        )
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Messages> {
+        match &self.code {
+            Some(code) => account::login(code.clone())
+                .map(|result| Messages::Login(Message::LoggedIn(result))),
+            None => Subscription::none(),
+        }
+    }
+}
+
+impl From<Login> for Screens {
+    fn from(value: Login) -> Self {
+        Screens::Login(value)
+    }
+}
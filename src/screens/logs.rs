@@ -0,0 +1,290 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use iced::widget::{
+    button, checkbox, column, pick_list, row, scrollable, text, text_input, Column,
+};
+use iced::{Alignment, Color, Command, Element, Length, Subscription};
+use tokio::fs;
+
+use crate::instance::Instance;
+use crate::log::{Level, Line, Source};
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::{log, process, Errors};
+
+const MAX_LINES: usize = 20000;
+
+const SHOWN_LINES: usize = 500;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Logs {
+    main: Main,
+    instance: Instance,
+    archives: Vec<PathBuf>,
+    file: LogFile,
+    lines: Vec<Line>,
+    pending: Vec<Line>,
+    levels: HashSet<Level>,
+    search: String,
+    paused: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    Lines(Vec<Line>),
+    FileSelected(LogFile),
+    Loaded(Result<Vec<Line>, Errors>),
+    LevelToggled(Level, bool),
+    SearchChanged(String),
+    TogglePause,
+    Export,
+    Exported(Result<(), Errors>),
+    Clear,
+    Cleared,
+    Back,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LogFile {
+    Live,
+    Archive(PathBuf),
+}
+
+impl Display for LogFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogFile::Live => f.write_str("Live"),
+            LogFile::Archive(path) => f.write_str(
+                &path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+impl Logs {
+    pub(crate) fn new(main: Main, instance: Instance) -> Self {
+        Logs {
+            main,
+            archives: log::archives(instance.path()),
+            instance,
+            file: LogFile::Live,
+            lines: vec![],
+            pending: vec![],
+            levels: Level::ALL.into_iter().collect(),
+            search: String::new(),
+            paused: false,
+        }
+    }
+
+    fn visible(&self) -> impl Iterator<Item = &Line> {
+        let search = self.search.to_lowercase();
+        self.lines.iter().filter(move |line| {
+            self.levels.contains(&line.level)
+                && (search.is_empty() || line.message.to_lowercase().contains(&search))
+        })
+    }
+
+    fn append(&mut self, lines: Vec<Line>) {
+        self.lines.extend(lines);
+        if self.lines.len() > MAX_LINES {
+            self.lines.drain(..self.lines.len() - MAX_LINES);
+        }
+    }
+}
+
+impl Screen for Logs {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::Lines(lines) => {
+                if self.paused {
+                    self.pending.extend(lines);
+                    (Command::none(), None)
+                } else {
+                    self.append(lines);
+                    (scroll_to_end(), None)
+                }
+            }
+            Message::FileSelected(file) => {
+                self.lines.clear();
+                self.pending.clear();
+                self.file = file.clone();
+                match file {
+                    LogFile::Live => (Command::none(), None),
+                    LogFile::Archive(path) => (
+                        Command::perform(log::read(path), |result| {
+                            Messages::Logs(Message::Loaded(result))
+                        }),
+                        None,
+                    ),
+                }
+            }
+            Message::Loaded(Ok(lines)) => {
+                self.append(lines);
+                (scroll_to_end(), None)
+            }
+            Message::LevelToggled(level, enabled) => {
+                if enabled {
+                    self.levels.insert(level);
+                } else {
+                    self.levels.remove(&level);
+                }
+                (Command::none(), None)
+            }
+            Message::SearchChanged(search) => {
+                self.search = search;
+                (Command::none(), None)
+            }
+            Message::TogglePause => {
+                self.paused = !self.paused;
+                if self.paused {
+                    (Command::none(), None)
+                } else {
+                    let pending = std::mem::take(&mut self.pending);
+                    self.append(pending);
+                    (scroll_to_end(), None)
+                }
+            }
+            Message::Export => {
+                let content = self
+                    .visible()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let file_name = format!("{}-{}.log", self.instance.name(), self.file);
+                (
+                    Command::perform(export(file_name, content), |result| {
+                        Messages::Logs(Message::Exported(result))
+                    }),
+                    None,
+                )
+            }
+            Message::Exported(Ok(())) => (Command::none(), None),
+            Message::Clear => (
+                Command::perform(process::forget(self.instance.id()), |_| {
+                    Messages::Logs(Message::Cleared)
+                }),
+                None,
+            ),
+            Message::Cleared => {
+                self.lines.clear();
+                self.pending.clear();
+                (Command::none(), None)
+            }
+            Message::Loaded(Err(error)) | Message::Exported(Err(error)) => (
+                Command::none(),
+                Some(Error::new(error, Box::new(self.clone().into())).into()),
+            ),
+            Message::Back => (Command::none(), Some(self.main.clone().into())),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let files = [LogFile::Live]
+            .into_iter()
+            .chain(self.archives.iter().cloned().map(LogFile::Archive))
+            .collect::<Vec<_>>();
+        let levels = Level::ALL.into_iter().map(|level| {
+            checkbox(
+                level.to_string(),
+                self.levels.contains(&level),
+                move |enabled| Messages::Logs(Message::LevelToggled(level, enabled)),
+            )
+            .into()
+        });
+        let controls = row(levels.collect())
+            .push(
+                text_input("Search", &self.search)
+                    .on_input(|search| Messages::Logs(Message::SearchChanged(search)))
+                    .width(200),
+            )
+            .spacing(10)
+            .align_items(Alignment::Center);
+
+        let visible = self.visible().collect::<Vec<_>>();
+        let lines = Column::with_children(
+            visible[visible.len().saturating_sub(SHOWN_LINES)..]
+                .iter()
+                .map(|line| text(line.to_string()).size(14).style(color(line)).into())
+                .collect(),
+        );
+
+        centering_container(
+            column![
+                row![
+                    text(format!("Logs of {}", self.instance.name())),
+                    pick_list(files, Some(self.file.clone()), |file| {
+                        Messages::Logs(Message::FileSelected(file))
+                    }),
+                    button(if self.paused { "Resume" } else { "Pause" }).on_press_maybe(
+                        (self.file == LogFile::Live)
+                            .then_some(Messages::Logs(Message::TogglePause))
+                    ),
+                    button("Clear").on_press_maybe(
+                        (self.file == LogFile::Live).then_some(Messages::Logs(Message::Clear))
+                    ),
+                    button("Export").on_press(Messages::Logs(Message::Export)),
+                    button("Back").on_press(Messages::Logs(Message::Back)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                controls,
+                scrollable(lines.width(Length::Fill))
+                    .id(scrollable::Id::new("logs"))
+                    .height(Length::Fill),
+            ]
+            .spacing(10),
+        )
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Messages> {
+        match self.file {
+            LogFile::Live => log::tail(self.instance.id(), log::latest(self.instance.path()))
+                .map(|lines| Messages::Logs(Message::Lines(lines))),
+            LogFile::Archive(_) => Subscription::none(),
+        }
+    }
+}
+
+impl From<Logs> for Screens {
+    fn from(value: Logs) -> Self {
+        Screens::Logs(value)
+    }
+}
+
+fn scroll_to_end() -> Command<Messages> {
+    scrollable::snap_to(scrollable::Id::new("logs"), scrollable::RelativeOffset::END)
+}
+
+fn color(line: &Line) -> Color {
+    match line.level {
+        Level::Fatal | Level::Error => Color::from_rgb8(0xf0, 0x60, 0x60),
+        Level::Warn => Color::from_rgb8(0xf0, 0xc0, 0x50),
+        Level::Debug | Level::Trace => Color::from_rgb8(0x90, 0x90, 0x90),
+        Level::Info if line.source == Source::Stderr => Color::from_rgb8(0xf0, 0x90, 0x90),
+        Level::Info => Color::WHITE,
+    }
+}
+
+async fn export(file_name: String, content: String) -> Result<(), Errors> {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_title("Export Log")
+        .set_file_name(file_name)
+        .add_filter("Log", &["log", "txt"])
+        .save_file()
+        .await
+    else {
+        return Ok(());
+    };
+    fs::write(file.path(), content)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
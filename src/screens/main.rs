@@ -1,40 +1,294 @@
-use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
 use std::path::PathBuf;
-use std::process::Command as Program;
+use std::time::Duration;
 
-use iced::widget::{button, row};
-use iced::{Alignment, Command, Element};
-use serde_json::{json, Value};
+use iced::widget::{button, checkbox, column, pick_list, row, text, text_input};
+use iced::{theme, Alignment, Command, Element, Subscription};
+use tokio::process::Command as Program;
+use uuid::Uuid;
 
-use crate::instance::Instance;
+use crate::account::Account;
+use crate::api::mojang;
+use crate::dependencies::Problem;
+use crate::instance::{collect_instances, Instance};
+use crate::java::Runtime;
+use crate::launch::{LaunchMode, Session};
+use crate::modpack::Modpack;
+use crate::process::Run;
+use crate::screens::accounts::Accounts;
+use crate::screens::crash::Crash;
+use crate::screens::create_instance::CreateInstance;
 use crate::screens::error::Error;
-use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::screens::import::{select_modpack, Import};
+use crate::screens::installing::Installing;
+use crate::screens::logs::Logs;
+use crate::screens::mods::Mods;
+use crate::screens::problems::Problems;
+use crate::screens::{centering_container, create_instance, Messages, Screen, Screens};
+use crate::store::Collected;
 use crate::widget::instance_card;
-use crate::{paths, Errors};
+use crate::{
+    account, dependencies, install, java, launch, process, profile, store, updates, Errors, CONFIG,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct Main {
     launcher_path: PathBuf,
     instances: Vec<Instance>,
-    current_hovered: (usize, bool),
+    current_hovered: Option<Uuid>,
+    running: HashSet<Uuid>,
+    selected: Option<(Uuid, String)>,
+    runtimes: Vec<Runtime>,
+    launch_mode: LaunchMode,
+    offline_username: String,
+    accounts: Vec<Account>,
+    accounts_unreadable: bool,
+    default_account: Option<Uuid>,
+    updates: HashMap<Uuid, usize>,
+    reclaimed: Option<Collected>,
+    confirm_delete: bool,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum Message {
     AddInstance,
-    NewInstance(Result<Instance, Errors>),
-    TryRun(usize),
-    Run(Result<(), Errors>),
+    Select(Uuid),
+    NameChanged(String),
+    Rename,
+    RuntimesScanned(Vec<Runtime>),
+    JavaSelected(Java),
+    IconSelected(Icon),
+    Updated(Box<Result<Instance, Errors>>),
+    Delete,
+    ConfirmDelete,
+    CancelDelete,
+    Deleted(Uuid, Result<(), Errors>),
+    Sync,
+    Synced(Result<(), Errors>),
+    Restore,
+    Restored(Result<(), Errors>),
+    DirectLaunchToggled(bool),
+    OfflineUsernameChanged(String),
+    OfflineUsernameSubmitted,
+    Accounts,
+    AccountSelected(Player),
+    TryRun(Uuid),
+    Checked(Uuid, Result<Vec<Problem>, Errors>),
+    Kill(Uuid),
+    Logs(Uuid),
+    AnalyzeCrash,
+    Mods,
+    CrashRecorded(Box<Result<Instance, Errors>>),
+    Process(process::Event),
+    UpdatesChecked(Uuid, Result<usize, Errors>),
+    CleanUp,
+    CleanedUp(Result<Collected, Errors>),
+    Import,
+    ModpackSelected(Option<Box<Result<Modpack, Errors>>>),
+}
+
+const DEFAULT_USERNAME: &str = "Player";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Java {
+    Automatic,
+    Runtime(Runtime),
+}
+
+impl Display for Java {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Java::Automatic => f.write_str("Automatic Java"),
+            Java::Runtime(runtime) => runtime.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Icon {
+    Default,
+    Block(String),
+}
+
+impl Display for Icon {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Icon::Default => f.write_str("Default icon"),
+            Icon::Block(name) => f.write_str(&name.replace('_', " ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Player {
+    Default,
+    Account(Uuid, String),
+}
+
+impl Display for Player {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Player::Default => f.write_str("Default account"),
+            Player::Account(_, name) => f.write_str(name),
+        }
+    }
+}
+
+enum Launch {
+    Launcher(PathBuf),
+    Direct(Session),
+    Account(Uuid),
 }
 
 impl Main {
-    pub(crate) fn new(launcher_path: PathBuf, instances: Vec<Instance>) -> Self {
-        Main {
+    pub(crate) async fn load(launcher_path: PathBuf) -> Result<Self, Errors> {
+        let instances = collect_instances().await?;
+        let (accounts, accounts_unreadable) = match account::load().await {
+            Ok(accounts) => (accounts, false),
+            Err(_) => (vec![], true),
+        };
+        let config = CONFIG.lock().await;
+        Ok(Main {
             launcher_path,
             instances,
-            current_hovered: (0, false),
+            current_hovered: None,
+            running: HashSet::new(),
+            selected: None,
+            runtimes: vec![],
+            launch_mode: config.launch_mode,
+            offline_username: config
+                .offline_username
+                .clone()
+                .unwrap_or_else(|| String::from(DEFAULT_USERNAME)),
+            accounts,
+            accounts_unreadable,
+            default_account: config.default_account,
+            updates: HashMap::new(),
+            reclaimed: None,
+            confirm_delete: false,
+        })
+    }
+
+    pub(crate) fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    pub(crate) fn set_accounts(&mut self, accounts: Vec<Account>) {
+        self.accounts = accounts;
+        self.accounts_unreadable = false;
+    }
+
+    pub(crate) fn default_account(&self) -> Option<Uuid> {
+        self.default_account
+    }
+
+    pub(crate) fn set_default_account(&mut self, default_account: Option<Uuid>) {
+        self.default_account = default_account;
+    }
+
+    pub(crate) fn add_instance(&mut self, instance: Instance) {
+        self.instances.push(instance);
+    }
+
+    pub(crate) fn check_updates(&self) -> Command<Messages> {
+        Command::batch(
+            self.instances
+                .iter()
+                .filter(|instance| !instance.mod_sources().is_empty())
+                .map(|instance| {
+                    let id = instance.id();
+                    Command::perform(updates::count(instance.clone()), move |result| {
+                        Messages::Main(Message::UpdatesChecked(id, result))
+                    })
+                }),
+        )
+    }
+
+    pub(crate) fn set_updates(&mut self, id: Uuid, count: usize) {
+        self.updates.insert(id, count);
+    }
+
+    pub(crate) fn set_running(&mut self, running: HashSet<Uuid>) {
+        self.running = running;
+    }
+
+    pub(crate) fn update_instance(&mut self, instance: Instance) {
+        if let Some(existing) = self
+            .instances
+            .iter_mut()
+            .find(|existing| existing.id() == instance.id())
+        {
+            *existing = instance;
+        }
+    }
+
+    pub(crate) fn start(mut self, instance: Instance) -> (Command<Messages>, Option<Screens>) {
+        if install::needs_installer(&instance) {
+            (
+                Command::none(),
+                Some(Installing::new(self, instance).into()),
+            )
+        } else {
+            let command = self.launch(instance);
+            (command, Some(self.into()))
         }
     }
+
+    pub(crate) fn launch(&mut self, instance: Instance) -> Command<Messages> {
+        self.update_instance(instance.clone());
+        let launch = match self.launch_mode {
+            LaunchMode::Launcher => Launch::Launcher(self.launcher_path.clone()),
+            LaunchMode::Direct => match self.account_for(&instance) {
+                Some(id) => Launch::Account(id),
+                None => Launch::Direct(Session::offline(&self.offline_username)),
+            },
+        };
+        Command::perform(run(launch, instance), |result| {
+            Messages::Main(Message::Updated(Box::new(result)))
+        })
+    }
+
+    fn instance(&self, id: Uuid) -> Option<&Instance> {
+        self.instances.iter().find(|instance| instance.id() == id)
+    }
+
+    fn selected_instance(&self) -> Option<&Instance> {
+        self.selected
+            .as_ref()
+            .and_then(|(id, _)| self.instance(*id))
+    }
+
+    fn account_for(&self, instance: &Instance) -> Option<Uuid> {
+        instance
+            .account()
+            .into_iter()
+            .chain(self.default_account)
+            .chain(self.accounts.first().map(|account| account.id))
+            .find(|id| self.accounts.iter().any(|account| account.id == *id))
+    }
+
+    fn save_settings(&self) -> Command<Messages> {
+        let launch_mode = self.launch_mode;
+        let offline_username = self.offline_username.clone();
+        Command::perform(
+            async move {
+                let mut config = CONFIG.lock().await;
+                config.launch_mode = launch_mode;
+                config.offline_username = Some(offline_username);
+                config.save().await
+            },
+            Messages::Save,
+        )
+    }
+
+    fn error(&self, error: Errors) -> Option<Screens> {
+        Some(Screens::Error(Error::new(
+            error,
+            Box::new(Screens::Main(self.clone())),
+        )))
+    }
 }
 
 impl Screen for Main {
@@ -43,74 +297,471 @@ impl Screen for Main {
     fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
         match message {
             Message::AddInstance => (
-                Command::perform(Instance::new("Test"), |result| {
-                    Messages::Main(Message::NewInstance(result))
+                Command::perform(mojang::manifest(), |result| {
+                    Messages::CreateInstance(create_instance::Message::ManifestLoaded(result))
                 }),
-                None,
+                Some(CreateInstance::new(self.clone()).into()),
             ),
-            Message::NewInstance(result) => (
+            Message::Select(id) => {
+                let Some(name) = self
+                    .instance(id)
+                    .map(|instance| instance.name().to_string())
+                else {
+                    return (Command::none(), None);
+                };
+                self.confirm_delete = false;
+                self.selected = Some((id, name));
+                (
+                    if self.runtimes.is_empty() {
+                        Command::perform(java::scan(), |runtimes| {
+                            Messages::Main(Message::RuntimesScanned(runtimes))
+                        })
+                    } else {
+                        Command::none()
+                    },
+                    None,
+                )
+            }
+            Message::RuntimesScanned(runtimes) => {
+                self.runtimes = runtimes;
+                (Command::none(), None)
+            }
+            Message::JavaSelected(java) => match self.selected_instance() {
+                Some(instance) => {
+                    let java_path = match java {
+                        Java::Automatic => None,
+                        Java::Runtime(runtime) => Some(runtime.path),
+                    };
+                    (
+                        Command::perform(set_java_path(instance.clone(), java_path), |result| {
+                            Messages::Main(Message::Updated(Box::new(result)))
+                        }),
+                        None,
+                    )
+                }
+                None => (Command::none(), None),
+            },
+            Message::NameChanged(name) => {
+                if let Some((_, selected_name)) = &mut self.selected {
+                    *selected_name = name;
+                }
+                (Command::none(), None)
+            }
+            Message::Rename => match self.selected.as_ref().zip(self.selected_instance()) {
+                Some(((_, name), instance)) => (
+                    Command::perform(rename(instance.clone(), name.clone()), |result| {
+                        Messages::Main(Message::Updated(Box::new(result)))
+                    }),
+                    None,
+                ),
+                None => (Command::none(), None),
+            },
+            Message::Updated(result) => (
                 Command::none(),
-                match result {
+                match *result {
                     Ok(instance) => {
-                        self.instances.push(instance);
+                        self.update_instance(instance);
 
                         None
                     }
-                    Err(error) => Some(Screens::Error(Error::new(
-                        error,
-                        Box::new(Screens::Main(self.clone())),
-                    ))),
+                    Err(error) => self.error(error),
                 },
             ),
-            Message::TryRun(i) => {
-                self.current_hovered = (i, true);
-                (
-                    Command::perform(
-                        run(self.launcher_path.clone(), self.instances[i].clone()),
-                        |result| Messages::Main(Message::Run(result)),
-                    ),
-                    None,
-                )
+            Message::Delete => {
+                self.confirm_delete = true;
+                (Command::none(), None)
+            }
+            Message::CancelDelete => {
+                self.confirm_delete = false;
+                (Command::none(), None)
             }
-            Message::Run(result) => (
+            Message::ConfirmDelete => {
+                self.confirm_delete = false;
+                match self.selected_instance().cloned() {
+                    Some(instance) if !self.running.contains(&instance.id()) => {
+                        self.selected = None;
+                        let id = instance.id();
+                        (
+                            Command::perform(delete(instance), move |result| {
+                                Messages::Main(Message::Deleted(id, result))
+                            }),
+                            None,
+                        )
+                    }
+                    _ => (Command::none(), None),
+                }
+            }
+            Message::Deleted(id, result) => (
+                Command::none(),
+                match result {
+                    Ok(_) => {
+                        self.instances.retain(|instance| instance.id() != id);
+                        self.selected = None;
+                        self.current_hovered = None;
+
+                        None
+                    }
+                    Err(error) => self.error(error),
+                },
+            ),
+            Message::Sync => (
+                Command::perform(profile::sync_profiles(self.instances.clone()), |result| {
+                    Messages::Main(Message::Synced(result))
+                }),
+                None,
+            ),
+            Message::Restore => (
+                Command::perform(profile::restore_profiles(), |result| {
+                    Messages::Main(Message::Restored(result))
+                }),
+                None,
+            ),
+            Message::DirectLaunchToggled(enabled) => {
+                self.launch_mode = if enabled {
+                    LaunchMode::Direct
+                } else {
+                    LaunchMode::Launcher
+                };
+                (self.save_settings(), None)
+            }
+            Message::OfflineUsernameChanged(offline_username) => {
+                self.offline_username = offline_username;
+                (Command::none(), None)
+            }
+            Message::OfflineUsernameSubmitted => (self.save_settings(), None),
+            Message::Accounts => {
+                let accounts = Accounts::new(self.clone());
+                (accounts.load_heads(), Some(accounts.into()))
+            }
+            Message::IconSelected(icon) => match self.selected_instance() {
+                Some(instance) => {
+                    let icon = match icon {
+                        Icon::Default => None,
+                        Icon::Block(name) => Some(name),
+                    };
+                    (
+                        Command::perform(set_icon(instance.clone(), icon), |result| {
+                            Messages::Main(Message::Updated(Box::new(result)))
+                        }),
+                        None,
+                    )
+                }
+                None => (Command::none(), None),
+            },
+            Message::AccountSelected(player) => match self.selected_instance() {
+                Some(instance) => {
+                    let account = match player {
+                        Player::Default => None,
+                        Player::Account(id, _) => Some(id),
+                    };
+                    (
+                        Command::perform(set_account(instance.clone(), account), |result| {
+                            Messages::Main(Message::Updated(Box::new(result)))
+                        }),
+                        None,
+                    )
+                }
+                None => (Command::none(), None),
+            },
+            Message::Import => (
+                Command::perform(select_modpack(), |result| {
+                    Messages::Main(Message::ModpackSelected(result.map(Box::new)))
+                }),
+                None,
+            ),
+            Message::ModpackSelected(result) => match result.map(|result| *result) {
+                Some(Ok(modpack)) => (
+                    Command::none(),
+                    Some(Import::new(self.clone(), modpack).into()),
+                ),
+                Some(Err(error)) => (Command::none(), self.error(error)),
+                None => (Command::none(), None),
+            },
+            Message::CleanUp => (
+                Command::perform(store::collect(), |result| {
+                    Messages::Main(Message::CleanedUp(result))
+                }),
+                None,
+            ),
+            Message::CleanedUp(result) => match result {
+                Ok(collected) => {
+                    self.reclaimed = Some(collected);
+                    (Command::none(), None)
+                }
+                Err(error) => (Command::none(), self.error(error)),
+            },
+            Message::Synced(result) | Message::Restored(result) => (
                 Command::none(),
                 match result {
                     Ok(_) => None,
-                    Err(error) => Some(Screens::Error(Error::new(
-                        error,
-                        Box::new(Screens::Main(self.clone())),
-                    ))),
+                    Err(error) => self.error(error),
                 },
             ),
+            Message::TryRun(id) => {
+                self.current_hovered = Some(id);
+                let Some(instance) = self.instance(id).filter(|_| !self.running.contains(&id))
+                else {
+                    return (Command::none(), None);
+                };
+                (
+                    Command::perform(dependencies::check(instance.clone()), move |result| {
+                        Messages::Main(Message::Checked(id, result))
+                    }),
+                    None,
+                )
+            }
+            Message::Checked(id, Ok(problems)) => {
+                let Some(instance) = self
+                    .instances
+                    .iter()
+                    .find(|instance| instance.id() == id)
+                    .cloned()
+                else {
+                    return (Command::none(), None);
+                };
+                if self.running.contains(&id) {
+                    (Command::none(), None)
+                } else if problems.is_empty() {
+                    self.clone().start(instance)
+                } else {
+                    (
+                        Command::none(),
+                        Some(Problems::new(self.clone(), instance, problems).into()),
+                    )
+                }
+            }
+            Message::Checked(_, Err(error)) => (
+                Command::none(),
+                Some(Error::new(error, Box::new(self.clone().into())).into()),
+            ),
+            Message::Kill(id) => (
+                Command::perform(process::kill(id), |_| {
+                    Messages::Main(Message::Process(process::Event::Killed))
+                }),
+                None,
+            ),
+            Message::Logs(id) => match self.instance(id) {
+                Some(instance) => (
+                    Command::none(),
+                    Some(Logs::new(self.clone(), instance.clone()).into()),
+                ),
+                None => (Command::none(), None),
+            },
+            Message::Process(event) => match event {
+                process::Event::Started(id) => {
+                    self.running.insert(id);
+                    (Command::none(), None)
+                }
+                process::Event::Exited(id, run) | process::Event::Crashed(id, run) => {
+                    self.running.remove(&id);
+                    let crashed = run.crashed();
+                    match self.instance(id) {
+                        Some(instance) => (
+                            Command::perform(record_run(instance.clone(), run), move |result| {
+                                Messages::Main(if crashed {
+                                    Message::CrashRecorded(Box::new(result))
+                                } else {
+                                    Message::Updated(Box::new(result))
+                                })
+                            }),
+                            None,
+                        ),
+                        None => (Command::none(), None),
+                    }
+                }
+                process::Event::Killed => (Command::none(), None),
+            },
+            Message::CrashRecorded(result) => match *result {
+                Ok(instance) => {
+                    self.update_instance(instance.clone());
+                    Crash::open(self.clone(), instance)
+                }
+                Err(error) => (Command::none(), self.error(error)),
+            },
+            Message::UpdatesChecked(id, Ok(count)) => {
+                self.set_updates(id, count);
+                (Command::none(), None)
+            }
+            Message::UpdatesChecked(_, Err(_)) => (Command::none(), None),
+            Message::Mods => match self.selected_instance() {
+                Some(instance) => Mods::open(self.clone(), instance.clone()),
+                None => (Command::none(), None),
+            },
+            Message::AnalyzeCrash => match self.selected_instance() {
+                Some(instance) => Crash::open(self.clone(), instance.clone()),
+                None => (Command::none(), None),
+            },
         }
     }
 
     fn view(&self) -> Element<'_, Messages> {
-        Element::from(centering_container(
-            row(self
-                .instances
-                .iter()
-                .enumerate()
-                .map(|(i, instance)| {
-                    instance_card::InstanceCard::new(
-                        Messages::Main(Message::TryRun(i)),
-                        if self.current_hovered.0 == i {
-                            self.current_hovered.1
-                        } else {
-                            false
-                        },
-                        instance,
-                    )
-                    .width(120)
-                    .height(150)
-                    .into()
-                })
-                .collect())
-            .push(button("Add").on_press(Messages::Main(Message::AddInstance)))
+        let instances = row(self
+            .instances
+            .iter()
+            .map(|instance| {
+                instance_card::InstanceCard::new(
+                    Messages::Main(Message::TryRun(instance.id())),
+                    Messages::Main(Message::Kill(instance.id())),
+                    Messages::Main(Message::Logs(instance.id())),
+                    Messages::Main(Message::Select(instance.id())),
+                    self.current_hovered == Some(instance.id()),
+                    self.running.contains(&instance.id()),
+                    instance,
+                )
+                .updates(self.updates.get(&instance.id()).copied().unwrap_or(0))
+                .width(120)
+                .height(150)
+                .into()
+            })
+            .collect())
+        .push(button("Add").on_press(Messages::Main(Message::AddInstance)))
+        .push(button("Import").on_press(Messages::Main(Message::Import)))
+        .push(button("Sync").on_press(Messages::Main(Message::Sync)))
+        .push(button("Restore").on_press(Messages::Main(Message::Restore)))
+        .push(button("Accounts").on_press(Messages::Main(Message::Accounts)))
+        .push(button("Clean up").on_press(Messages::Main(Message::CleanUp)))
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let mut content = column![instances]
             .spacing(10)
-            .align_items(Alignment::Center),
-        ))
-        // .explain(iced::Color::WHITE)
+            .align_items(Alignment::Center);
+        if self.accounts_unreadable {
+            content = content.push(text(
+                "Saved accounts could not be decrypted. Sign in again to replace them.",
+            ));
+        }
+        if let Some(collected) = self.reclaimed {
+            content = content.push(text(format!(
+                "Removed {} unused file(s) from the store, reclaiming {:.1} MB.",
+                collected.blobs,
+                collected.bytes as f64 / (1024.0 * 1024.0)
+            )));
+        }
+        if let Some(((_, name), instance)) = self.selected.as_ref().zip(self.selected_instance()) {
+            let selected_java = match instance.java_path() {
+                Some(java_path) => Java::Runtime(
+                    self.runtimes
+                        .iter()
+                        .find(|runtime| runtime.path == java_path)
+                        .cloned()
+                        .unwrap_or_else(|| Runtime {
+                            path: java_path.to_path_buf(),
+                            version: java_path.to_string_lossy().to_string(),
+                            major: 0,
+                            vendor: String::from("unknown"),
+                            arch: String::from("unknown"),
+                        }),
+                ),
+                None => Java::Automatic,
+            };
+            let java_options = [Java::Automatic]
+                .into_iter()
+                .chain(self.runtimes.iter().cloned().map(Java::Runtime))
+                .collect::<Vec<_>>();
+            let player =
+                |account: &Account| Player::Account(account.id, account.display_name().to_string());
+            let selected_player = instance
+                .account()
+                .and_then(|id| self.accounts.iter().find(|account| account.id == id))
+                .map_or(Player::Default, player);
+            let selected_icon = instance
+                .icon()
+                .map_or(Icon::Default, |icon| Icon::Block(icon.to_string()));
+            let icon_options = [Icon::Default]
+                .into_iter()
+                .chain(
+                    profile::ICONS
+                        .into_iter()
+                        .map(|icon| Icon::Block(icon.to_string())),
+                )
+                .collect::<Vec<_>>();
+            let player_options = [Player::Default]
+                .into_iter()
+                .chain(self.accounts.iter().map(player))
+                .collect::<Vec<_>>();
+            content = content.push(
+                row![
+                    pick_list(java_options, Some(selected_java), |java| {
+                        Messages::Main(Message::JavaSelected(java))
+                    })
+                    .width(250),
+                    pick_list(player_options, Some(selected_player), |player| {
+                        Messages::Main(Message::AccountSelected(player))
+                    })
+                    .width(150),
+                    pick_list(icon_options, Some(selected_icon), |icon| {
+                        Messages::Main(Message::IconSelected(icon))
+                    })
+                    .width(150),
+                    text_input("Instance name", name)
+                        .on_input(|name| Messages::Main(Message::NameChanged(name)))
+                        .width(200),
+                    button("Rename").on_press_maybe(if name.trim().is_empty() {
+                        None
+                    } else {
+                        Some(Messages::Main(Message::Rename))
+                    }),
+                    button("Mods").on_press(Messages::Main(Message::Mods)),
+                    button("Delete").on_press_maybe(
+                        (!self.running.contains(&instance.id()))
+                            .then_some(Messages::Main(Message::Delete))
+                    ),
+                ]
+                .spacing(10),
+            );
+            if self.confirm_delete {
+                content = content.push(
+                    row![
+                        text(format!(
+                            "Delete {} and all of its files? This cannot be undone.",
+                            instance.name()
+                        )),
+                        button("Delete")
+                            .style(theme::Button::Destructive)
+                            .on_press(Messages::Main(Message::ConfirmDelete)),
+                        button("Cancel").on_press(Messages::Main(Message::CancelDelete)),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+                );
+            }
+            let mut summary = row![text(run_summary(instance))]
+                .spacing(10)
+                .align_items(Alignment::Center);
+            if instance.last_run().is_some_and(Run::crashed) {
+                summary = summary
+                    .push(button("Analyze crash").on_press(Messages::Main(Message::AnalyzeCrash)));
+            }
+            content = content.push(summary);
+        }
+
+        let mut settings = row![checkbox(
+            "Launch directly",
+            self.launch_mode == LaunchMode::Direct,
+            |enabled| Messages::Main(Message::DirectLaunchToggled(enabled))
+        )]
+        .spacing(10)
+        .align_items(Alignment::Center);
+        if self.launch_mode == LaunchMode::Launcher {
+            settings =
+                settings.push(text("Play time and crash detection need direct launching").size(14));
+        }
+        if self.launch_mode == LaunchMode::Direct && self.accounts.is_empty() {
+            settings = settings.push(
+                text_input("Offline username", &self.offline_username)
+                    .on_input(|name| Messages::Main(Message::OfflineUsernameChanged(name)))
+                    .on_submit(Messages::Main(Message::OfflineUsernameSubmitted))
+                    .width(200),
+            );
+        }
+        content = content.push(settings);
+
+        Element::from(centering_container(content))
+    }
+
+    fn subscription(&self) -> Subscription<Messages> {
+        process::events().map(|event| Messages::Main(Message::Process(event)))
     }
 }
 
@@ -120,37 +771,86 @@ impl From<Main> for Screens {
     }
 }
 
-async fn run(launcher_path: PathBuf, instance: Instance) -> Result<(), Errors> {
-    println!("Selected path: {:?}", launcher_path);
-
-    add_profile(instance).await?;
-
-    Program::new(launcher_path)
-        .spawn()
-        .map_err(|error| Errors::Io(error.kind()))?;
-    Ok(())
-}
-
-async fn add_profile(instance: Instance) -> Result<(), Errors> {
-    let path = paths::PROFILE.clone()?;
-    let mut value = serde_json::from_str::<Value>(
-        &fs::read_to_string(&path).map_err(|error| Errors::Io(error.kind()))?,
-    )
-    .map_err(|error| Errors::Json(error.to_string()))?;
-    if !value["profiles"].is_object() {
-        value["profiles"] = json!({});
-    }
-    let profiles = &mut value["profiles"];
-    profiles["aethon"] = json!({
-        "name": "Aethon",
-        "type": "custom",
-        "icon": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAIAAAACAAQMAAAD58POIAAAABlBMVEUAAAD4APit1uGJAAAAI0lEQVRIx2P4DwUMMDAqMCowKjAqQKTAaDCMCowKjAqQKQAABpD8LlM5SL4AAAAASUVORK5CYII",
-        "lastVersionId": "latest-release",
-        "gameDir": instance.path(),
-    });
-    fs::write(
-        &path,
-        serde_json::to_string(&value).map_err(|error| Errors::Json(error.to_string()))?,
-    )
-    .map_err(|error| Errors::Io(error.kind()))
+async fn run(launch: Launch, instance: Instance) -> Result<Instance, Errors> {
+    let instance = install::install(instance).await?;
+
+    let child = match launch {
+        Launch::Launcher(launcher_path) => {
+            profile::add_profile(&instance).await?;
+
+            let mut launcher = Program::new(launcher_path)
+                .spawn()
+                .map_err(|error| Errors::Io(error.kind()))?;
+            tokio::spawn(async move { launcher.wait().await });
+            return Ok(instance);
+        }
+        Launch::Direct(session) => launch::launch(&instance, session).await?,
+        Launch::Account(id) => launch::launch(&instance, account::session(id).await?).await?,
+    };
+    process::supervise(instance.id(), child).await;
+    Ok(instance)
+}
+
+async fn record_run(mut instance: Instance, run: Run) -> Result<Instance, Errors> {
+    instance.record_run(run).await?;
+    Ok(instance)
+}
+
+fn run_summary(instance: &Instance) -> String {
+    let play_time = format_duration(instance.play_time());
+    match instance.last_run() {
+        Some(run) => {
+            let outcome = match run.exit_code {
+                _ if run.killed => String::from("was killed"),
+                Some(0) => String::from("exited normally"),
+                Some(code) => format!("crashed with exit code {code}"),
+                None => String::from("crashed"),
+            };
+            format!(
+                "Played {play_time}, last session lasted {} and {outcome}",
+                format_duration(run.duration)
+            )
+        }
+        None => format!("Played {play_time}"),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {}m", minutes / 60, minutes % 60)
+}
+
+async fn set_java_path(
+    mut instance: Instance,
+    java_path: Option<PathBuf>,
+) -> Result<Instance, Errors> {
+    instance.set_java_path(java_path).await?;
+    profile::update_profile(&instance).await?;
+    Ok(instance)
+}
+
+async fn set_icon(mut instance: Instance, icon: Option<String>) -> Result<Instance, Errors> {
+    instance.set_icon(icon).await?;
+    profile::update_profile(&instance).await?;
+    Ok(instance)
+}
+
+async fn set_account(mut instance: Instance, account: Option<Uuid>) -> Result<Instance, Errors> {
+    instance.set_account(account).await?;
+    Ok(instance)
+}
+
+async fn rename(mut instance: Instance, name: String) -> Result<Instance, Errors> {
+    instance.rename(name.trim()).await?;
+    profile::update_profile(&instance).await?;
+    Ok(instance)
+}
+
+async fn delete(instance: Instance) -> Result<(), Errors> {
+    if process::running().await.contains(&instance.id()) {
+        return Err(Errors::Io(ErrorKind::ResourceBusy));
+    }
+    profile::remove_profile(&instance).await?;
+    process::forget(instance.id()).await;
+    instance.delete().await
 }
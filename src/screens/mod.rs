@@ -1,22 +1,47 @@
 use std::fmt::Debug;
 
 use iced::widget::{container, Container};
-use iced::{Command, Element, Length};
+use iced::{Command, Element, Length, Subscription};
 
+use crate::screens::accounts::Accounts;
+use crate::screens::crash::Crash;
+use crate::screens::create_instance::CreateInstance;
+use crate::screens::curseforge::CurseForge;
 use crate::screens::error::Error;
 use crate::screens::folder_warn::FolderNotEmptyWarn;
+use crate::screens::import::Import;
+use crate::screens::installing::Installing;
 use crate::screens::instance_warn::SingleInstanceWarn;
+use crate::screens::login::Login;
+use crate::screens::logs::Logs;
 use crate::screens::main::Main;
+use crate::screens::modrinth::Modrinth;
+use crate::screens::mods::Mods;
+use crate::screens::problems::Problems;
 use crate::screens::setup::Setup;
 use crate::screens::startup::Startup;
+use crate::screens::updates::Updates;
 use crate::Errors;
 
+pub(crate) mod accounts;
+pub(crate) mod crash;
+pub(crate) mod create_instance;
+pub(crate) mod curseforge;
+pub(crate) mod downloads;
 pub(crate) mod error;
 pub(crate) mod folder_warn;
+pub(crate) mod import;
+pub(crate) mod installing;
 pub(crate) mod instance_warn;
+pub(crate) mod login;
+pub(crate) mod logs;
 pub(crate) mod main;
+pub(crate) mod modrinth;
+pub(crate) mod mods;
+pub(crate) mod problems;
 pub(crate) mod setup;
 pub(crate) mod startup;
+pub(crate) mod updates;
 
 pub(crate) fn centering_container<'a, Message>(
     content: impl Into<Element<'a, Message>>,
@@ -35,6 +60,10 @@ pub(crate) trait Screen: Debug + Clone + Into<Screens> {
     fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>);
 
     fn view(&self) -> Element<'_, Messages>;
+
+    fn subscription(&self) -> Subscription<Messages> {
+        Subscription::none()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -44,16 +73,41 @@ pub(crate) enum Screens {
     SingleInstanceWarn(SingleInstanceWarn),
     Setup(Setup),
     Main(Main),
+    CreateInstance(CreateInstance),
+    Installing(Installing),
+    Login(Login),
+    Accounts(Accounts),
+    Logs(Logs),
+    Crash(Crash),
+    Mods(Mods),
+    Problems(Problems),
+    Modrinth(Modrinth),
+    CurseForge(CurseForge),
+    Updates(Updates),
+    Import(Import),
     Error(Error),
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum Messages {
     Save(Result<(), Errors>),
+    Downloads(downloads::Message),
     Startup(startup::Message),
     FolderNotEmptyWarn(folder_warn::Message),
     SingleInstanceWarn(instance_warn::Message),
     Setup(setup::Message),
     Main(main::Message),
+    CreateInstance(create_instance::Message),
+    Installing(installing::Message),
+    Login(login::Message),
+    Accounts(accounts::Message),
+    Logs(logs::Message),
+    Crash(crash::Message),
+    Mods(mods::Message),
+    Problems(problems::Message),
+    Modrinth(modrinth::Message),
+    CurseForge(curseforge::Message),
+    Updates(updates::Message),
+    Import(import::Message),
     Error(error::Message),
 }
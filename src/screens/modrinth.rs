@@ -0,0 +1,446 @@
+use std::fmt::{Display, Formatter};
+
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, pick_list, row, scrollable, text,
+    text_input, Column,
+};
+use iced::{theme, Alignment, Command, Element, Length};
+
+use crate::api::modrinth;
+use crate::api::modrinth::{Facets, Project, SearchResults, Version};
+use crate::instance::Instance;
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::mods::Mods;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::Errors;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Modrinth {
+    main: Main,
+    instance: Instance,
+    query: String,
+    facets: Facets,
+    categories: Vec<Category>,
+    results: Option<SearchResults>,
+    project: Option<Project>,
+    versions: Option<Vec<Version>>,
+    selected_version: Option<usize>,
+    status: Option<String>,
+    installing: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    QueryChanged(String),
+    Search,
+    Searched(Result<SearchResults, Errors>),
+    Page(usize),
+    CategoriesLoaded(Result<Vec<String>, Errors>),
+    CategorySelected(Category),
+    GameVersionToggled(bool),
+    LoaderToggled(bool),
+    Open(String),
+    ProjectLoaded(Result<Project, Errors>),
+    VersionsLoaded(String, Result<Vec<Version>, Errors>),
+    SelectVersion(usize),
+    Install(usize),
+    Installed(Box<(Instance, Result<Vec<String>, Errors>)>),
+    Link,
+    Linked(Box<Result<(Instance, usize), Errors>>),
+    Back,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Category {
+    Any,
+    Named(String),
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Category::Any => f.write_str("Any category"),
+            Category::Named(name) => f.write_str(name),
+        }
+    }
+}
+
+impl Modrinth {
+    pub(crate) fn open(main: Main, instance: Instance) -> (Command<Messages>, Option<Screens>) {
+        let modrinth = Modrinth {
+            facets: Facets {
+                game_version: Some(instance.minecraft_version().to_string()),
+                loader: Some(instance.loader()),
+                category: None,
+            },
+            main,
+            instance,
+            query: String::new(),
+            categories: vec![Category::Any],
+            results: None,
+            project: None,
+            versions: None,
+            selected_version: None,
+            status: None,
+            installing: false,
+        };
+        (
+            Command::batch(vec![
+                modrinth.search(0),
+                Command::perform(modrinth::categories(), |result| {
+                    Messages::Modrinth(Message::CategoriesLoaded(result))
+                }),
+            ]),
+            Some(modrinth.into()),
+        )
+    }
+
+    fn search(&self, offset: usize) -> Command<Messages> {
+        Command::perform(
+            modrinth::search(self.query.clone(), self.facets.clone(), offset),
+            |result| Messages::Modrinth(Message::Searched(result)),
+        )
+    }
+
+    fn error(&self, error: Errors) -> (Command<Messages>, Option<Screens>) {
+        (
+            Command::none(),
+            Some(Error::new(error, Box::new(self.clone().into())).into()),
+        )
+    }
+
+    fn details<'a>(&'a self, project: &'a Project) -> Element<'a, Messages> {
+        let mut details = column![
+            text(&project.title).size(24),
+            text(&project.description),
+            text(format!(
+                "{} downloads, {}",
+                project.downloads,
+                project.categories.join(", ")
+            ))
+            .size(14),
+        ]
+        .spacing(5);
+        let Some(versions) = &self.versions else {
+            return details.push(text("Loading versions...")).into();
+        };
+        if versions.is_empty() {
+            return details
+                .push(text(format!(
+                    "No version supports Minecraft {} with {}",
+                    self.instance.minecraft_version(),
+                    self.instance.loader()
+                )))
+                .into();
+        }
+        for (i, version) in versions.iter().enumerate() {
+            let is_selected = self.selected_version == Some(i);
+            details = details.push(
+                row![
+                    button(
+                        column![
+                            text(&version.version_number),
+                            text(format!(
+                                "{}, {}, {}, {}",
+                                version.version_type,
+                                version.loaders.join("/"),
+                                version.game_versions.join(", "),
+                                version.date_published.get(..10).unwrap_or_default()
+                            ))
+                            .size(12),
+                        ]
+                        .spacing(2),
+                    )
+                    .style(if is_selected {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    })
+                    .width(Length::Fill)
+                    .on_press(Messages::Modrinth(Message::SelectVersion(i))),
+                    button("Install").on_press_maybe(
+                        (!self.installing).then_some(Messages::Modrinth(Message::Install(i)))
+                    ),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            );
+            if is_selected {
+                if let Some(changelog) = &version.changelog {
+                    details = details.push(text(changelog).size(14));
+                }
+            }
+        }
+        details.into()
+    }
+}
+
+impl Screen for Modrinth {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::QueryChanged(query) => {
+                self.query = query;
+                (Command::none(), None)
+            }
+            Message::Search => (self.search(0), None),
+            Message::Searched(Ok(results)) => {
+                self.results = Some(results);
+                (Command::none(), None)
+            }
+            Message::Page(offset) => (self.search(offset), None),
+            Message::CategoriesLoaded(Ok(categories)) => {
+                self.categories = [Category::Any]
+                    .into_iter()
+                    .chain(categories.into_iter().map(Category::Named))
+                    .collect();
+                (Command::none(), None)
+            }
+            Message::CategorySelected(category) => {
+                self.facets.category = match category {
+                    Category::Any => None,
+                    Category::Named(name) => Some(name),
+                };
+                (self.search(0), None)
+            }
+            Message::GameVersionToggled(enabled) => {
+                self.facets.game_version =
+                    enabled.then(|| self.instance.minecraft_version().to_string());
+                (self.search(0), None)
+            }
+            Message::LoaderToggled(enabled) => {
+                self.facets.loader = enabled.then(|| self.instance.loader());
+                (self.search(0), None)
+            }
+            Message::Open(project_id) => {
+                self.project = None;
+                self.versions = None;
+                self.selected_version = None;
+                let versions_id = project_id.clone();
+                (
+                    Command::batch(vec![
+                        Command::perform(modrinth::project(project_id.clone()), |result| {
+                            Messages::Modrinth(Message::ProjectLoaded(result))
+                        }),
+                        Command::perform(
+                            modrinth::versions(
+                                project_id,
+                                self.instance.minecraft_version().to_string(),
+                                self.instance.loader(),
+                            ),
+                            move |result| {
+                                Messages::Modrinth(Message::VersionsLoaded(
+                                    versions_id.clone(),
+                                    result,
+                                ))
+                            },
+                        ),
+                    ]),
+                    None,
+                )
+            }
+            Message::ProjectLoaded(Ok(project)) => {
+                self.project = Some(project);
+                (Command::none(), None)
+            }
+            Message::VersionsLoaded(project_id, Ok(versions)) => {
+                if self
+                    .project
+                    .as_ref()
+                    .is_none_or(|project| project.id == project_id)
+                {
+                    self.selected_version = (!versions.is_empty()).then_some(0);
+                    self.versions = Some(versions);
+                }
+                (Command::none(), None)
+            }
+            Message::SelectVersion(i) => {
+                self.selected_version = Some(i);
+                (Command::none(), None)
+            }
+            Message::Install(i) => {
+                let Some(version) = self.versions.as_ref().and_then(|versions| versions.get(i))
+                else {
+                    return (Command::none(), None);
+                };
+                self.installing = true;
+                self.status = Some(format!("Installing {}...", version.name));
+                (
+                    Command::perform(
+                        modrinth::install(self.instance.clone(), version.clone()),
+                        |result| Messages::Modrinth(Message::Installed(Box::new(result))),
+                    ),
+                    None,
+                )
+            }
+            Message::Installed(result) => {
+                self.installing = false;
+                let (instance, result) = *result;
+                self.main.update_instance(instance.clone());
+                self.instance = instance;
+                match result {
+                    Ok(installed) => {
+                        self.status = Some(format!("Installed {}", installed.join(", ")));
+                        (Command::none(), None)
+                    }
+                    Err(error) => {
+                        self.status = None;
+                        self.error(error)
+                    }
+                }
+            }
+            Message::Link => {
+                self.installing = true;
+                self.status = Some(String::from("Identifying local jars..."));
+                (
+                    Command::perform(modrinth::link(self.instance.clone()), |result| {
+                        Messages::Modrinth(Message::Linked(Box::new(result)))
+                    }),
+                    None,
+                )
+            }
+            Message::Linked(result) => {
+                self.installing = false;
+                match *result {
+                    Ok((instance, linked)) => {
+                        self.status = Some(format!("Linked {linked} jars to Modrinth"));
+                        self.main.update_instance(instance.clone());
+                        self.instance = instance;
+                        (Command::none(), None)
+                    }
+                    Err(error) => {
+                        self.status = None;
+                        self.error(error)
+                    }
+                }
+            }
+            Message::Back => Mods::open(self.main.clone(), self.instance.clone()),
+            Message::Searched(Err(error))
+            | Message::CategoriesLoaded(Err(error))
+            | Message::ProjectLoaded(Err(error))
+            | Message::VersionsLoaded(_, Err(error)) => self.error(error),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let category = self
+            .facets
+            .category
+            .clone()
+            .map_or(Category::Any, Category::Named);
+        let search = row![
+            text_input("Search Modrinth", &self.query)
+                .on_input(|query| Messages::Modrinth(Message::QueryChanged(query)))
+                .on_submit(Messages::Modrinth(Message::Search)),
+            pick_list(self.categories.clone(), Some(category), |category| {
+                Messages::Modrinth(Message::CategorySelected(category))
+            }),
+            checkbox(
+                format!("Minecraft {}", self.instance.minecraft_version()),
+                self.facets.game_version.is_some(),
+                |enabled| Messages::Modrinth(Message::GameVersionToggled(enabled)),
+            ),
+            checkbox(
+                self.instance.loader().to_string(),
+                self.facets.loader.is_some(),
+                |enabled| Messages::Modrinth(Message::LoaderToggled(enabled)),
+            ),
+            button("Search").on_press(Messages::Modrinth(Message::Search)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let mut results = Column::new().spacing(2);
+        let mut pages = row![].spacing(10).align_items(Alignment::Center);
+        match &self.results {
+            Some(search) => {
+                for hit in &search.hits {
+                    let is_selected = self
+                        .project
+                        .as_ref()
+                        .is_some_and(|project| project.id == hit.project_id);
+                    results = results.push(
+                        button(
+                            column![
+                                text(&hit.title),
+                                text(format!("by {}, {} downloads", hit.author, hit.downloads))
+                                    .size(12),
+                                text(&hit.description).size(14),
+                            ]
+                            .spacing(2),
+                        )
+                        .style(if is_selected {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        })
+                        .width(Length::Fill)
+                        .on_press(Messages::Modrinth(Message::Open(hit.project_id.clone()))),
+                    );
+                }
+                let end = search.offset + search.hits.len();
+                pages = pages
+                    .push(
+                        button("Previous").on_press_maybe((search.offset > 0).then(|| {
+                            Messages::Modrinth(Message::Page(
+                                search.offset.saturating_sub(modrinth::PAGE_SIZE),
+                            ))
+                        })),
+                    )
+                    .push(text(format!(
+                        "{}-{end} of {}",
+                        (search.offset + 1).min(end),
+                        search.total_hits
+                    )))
+                    .push(button("Next").on_press_maybe(
+                        (end < search.total_hits).then_some(Messages::Modrinth(Message::Page(end))),
+                    ));
+            }
+            None => results = results.push(text("Searching...")),
+        }
+
+        let details: Element<'_, Messages> = match &self.project {
+            Some(project) => self.details(project),
+            None => text("Select a project to see its versions").into(),
+        };
+
+        let mut footer = row![
+            button("Link local jars")
+                .on_press_maybe((!self.installing).then_some(Messages::Modrinth(Message::Link))),
+            horizontal_space(Length::Fill),
+            pages,
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+        if let Some(status) = &self.status {
+            footer = footer.push(text(status));
+        }
+        footer = footer.push(button("Back").on_press(Messages::Modrinth(Message::Back)));
+
+        centering_container(
+            column![
+                text(format!("Modrinth: {}", self.instance.name())).size(24),
+                search,
+                row![
+                    scrollable(results).width(Length::FillPortion(1)),
+                    container(scrollable(details))
+                        .width(Length::FillPortion(1))
+                        .padding(10),
+                ]
+                .spacing(10)
+                .height(Length::Fill),
+                footer,
+            ]
+            .spacing(10),
+        )
+        .into()
+    }
+}
+
+impl From<Modrinth> for Screens {
+    fn from(value: Modrinth) -> Self {
+        Screens::Modrinth(value)
+    }
+}
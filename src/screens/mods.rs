@@ -0,0 +1,475 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use iced::widget::image::Handle;
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, image, pick_list, row, scrollable, text,
+    text_input, Column,
+};
+use iced::{theme, Alignment, Command, Element, Length};
+
+use crate::instance::{Instance, ModSource};
+use crate::mods::{DependencyKind, ModInfo};
+use crate::screens::curseforge::CurseForge;
+use crate::screens::error::Error;
+use crate::screens::main::Main;
+use crate::screens::modrinth::Modrinth;
+use crate::screens::updates::Updates;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::{mods, paths, Errors};
+
+const ICON_SIZE: u16 = 32;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Mods {
+    main: Main,
+    instance: Instance,
+    mods: Option<Vec<(ModInfo, Option<Handle>)>>,
+    selected: Option<PathBuf>,
+    checked: HashSet<PathBuf>,
+    filter: String,
+    sort: Sort,
+    pending: Option<Pending>,
+}
+
+#[derive(Debug, Clone)]
+enum Pending {
+    Delete(Vec<PathBuf>),
+    Replace(Vec<PathBuf>, Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    Loaded(Result<Vec<ModInfo>, Errors>),
+    Select(PathBuf),
+    Check(PathBuf, bool),
+    CheckAll(bool),
+    FilterChanged(String),
+    SortSelected(Sort),
+    SetEnabled(Vec<PathBuf>, bool),
+    Delete,
+    Add,
+    Picked(Vec<PathBuf>),
+    Confirm,
+    Cancel,
+    OpenFolder,
+    Modrinth,
+    CurseForge,
+    Updates,
+    Done(Result<(), Errors>),
+    Back,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Sort {
+    #[default]
+    Name,
+    FileName,
+    Enabled,
+}
+
+impl Sort {
+    const ALL: [Sort; 3] = [Sort::Name, Sort::FileName, Sort::Enabled];
+}
+
+impl Display for Sort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Sort::Name => "Sort by name",
+            Sort::FileName => "Sort by file name",
+            Sort::Enabled => "Disabled first",
+        })
+    }
+}
+
+impl Mods {
+    pub(crate) fn open(main: Main, instance: Instance) -> (Command<Messages>, Option<Screens>) {
+        let mods = Mods {
+            main,
+            instance,
+            mods: None,
+            selected: None,
+            checked: HashSet::new(),
+            filter: String::new(),
+            sort: Sort::default(),
+            pending: None,
+        };
+        (mods.reload(), Some(mods.into()))
+    }
+
+    fn reload(&self) -> Command<Messages> {
+        Command::perform(mods::scan(mods::mods_dir(self.instance.path())), |result| {
+            Messages::Mods(Message::Loaded(result))
+        })
+    }
+
+    fn visible(&self) -> Vec<&(ModInfo, Option<Handle>)> {
+        let filter = self.filter.to_lowercase();
+        let mut visible = self
+            .mods
+            .iter()
+            .flatten()
+            .filter(|(info, _)| {
+                filter.is_empty()
+                    || info.name.to_lowercase().contains(&filter)
+                    || info.id.to_lowercase().contains(&filter)
+                    || info.path.to_string_lossy().to_lowercase().contains(&filter)
+            })
+            .collect::<Vec<_>>();
+        match self.sort {
+            Sort::Name => visible.sort_by_key(|(info, _)| info.name.to_lowercase()),
+            Sort::FileName => visible.sort_by_key(|(info, _)| info.path.clone()),
+            Sort::Enabled => {
+                visible.sort_by_key(|(info, _)| (info.enabled, info.name.to_lowercase()))
+            }
+        }
+        visible
+    }
+
+    fn perform(
+        &self,
+        future: impl std::future::Future<Output = Result<(), Errors>> + Send + 'static,
+    ) -> Command<Messages> {
+        Command::perform(future, |result| Messages::Mods(Message::Done(result)))
+    }
+}
+
+impl Screen for Mods {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::Loaded(Ok(mods)) => {
+                let paths = mods
+                    .iter()
+                    .map(|info| info.path.clone())
+                    .collect::<HashSet<_>>();
+                self.checked.retain(|path| paths.contains(path));
+                self.selected = self.selected.take().filter(|path| paths.contains(path));
+                self.mods = Some(
+                    mods.into_iter()
+                        .map(|info| {
+                            let icon = info.icon.clone().map(Handle::from_memory);
+                            (info, icon)
+                        })
+                        .collect(),
+                );
+                (Command::none(), None)
+            }
+            Message::Select(path) => {
+                self.selected = Some(path);
+                (Command::none(), None)
+            }
+            Message::Check(path, checked) => {
+                if checked {
+                    self.checked.insert(path);
+                } else {
+                    self.checked.remove(&path);
+                }
+                (Command::none(), None)
+            }
+            Message::CheckAll(checked) => {
+                self.checked = if checked {
+                    self.visible()
+                        .into_iter()
+                        .map(|(info, _)| info.path.clone())
+                        .collect()
+                } else {
+                    HashSet::new()
+                };
+                (Command::none(), None)
+            }
+            Message::FilterChanged(filter) => {
+                self.filter = filter;
+                (Command::none(), None)
+            }
+            Message::SortSelected(sort) => {
+                self.sort = sort;
+                (Command::none(), None)
+            }
+            Message::SetEnabled(paths, enabled) => {
+                self.checked.clear();
+                (self.perform(mods::set_enabled(paths, enabled)), None)
+            }
+            Message::Delete => {
+                self.pending = Some(Pending::Delete(self.checked.iter().cloned().collect()));
+                (Command::none(), None)
+            }
+            Message::Add => (
+                Command::perform(select_mods(), |files| {
+                    Messages::Mods(Message::Picked(files))
+                }),
+                None,
+            ),
+            Message::Picked(files) => {
+                let mods_path = mods::mods_dir(self.instance.path());
+                let installed = mods::installed(&mods_path, &files);
+                if files.is_empty() {
+                    (Command::none(), None)
+                } else if installed.is_empty() {
+                    (self.perform(mods::add(mods_path, files, false)), None)
+                } else {
+                    self.pending = Some(Pending::Replace(files, installed));
+                    (Command::none(), None)
+                }
+            }
+            Message::Confirm => match self.pending.take() {
+                Some(Pending::Delete(paths)) => {
+                    self.checked.clear();
+                    (self.perform(mods::delete(paths)), None)
+                }
+                Some(Pending::Replace(files, _)) => (
+                    self.perform(mods::add(mods::mods_dir(self.instance.path()), files, true)),
+                    None,
+                ),
+                None => (Command::none(), None),
+            },
+            Message::Cancel => {
+                self.pending = None;
+                (Command::none(), None)
+            }
+            Message::Modrinth => Modrinth::open(self.main.clone(), self.instance.clone()),
+            Message::CurseForge => CurseForge::open(self.main.clone(), self.instance.clone()),
+            Message::Updates => Updates::open(self.main.clone(), self.instance.clone()),
+            Message::OpenFolder => {
+                let mods_path = mods::mods_dir(self.instance.path());
+                (
+                    self.perform(async move {
+                        tokio::fs::create_dir_all(&mods_path)
+                            .await
+                            .map_err(|error| Errors::Io(error.kind()))?;
+                        paths::open(&mods_path)
+                    }),
+                    None,
+                )
+            }
+            Message::Done(Ok(())) => (self.reload(), None),
+            Message::Loaded(Err(error)) | Message::Done(Err(error)) => (
+                Command::none(),
+                Some(Error::new(error, Box::new(self.clone().into())).into()),
+            ),
+            Message::Back => (Command::none(), Some(self.main.clone().into())),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let header = row![
+            text(format!("Mods of {}", self.instance.name())).size(24),
+            horizontal_space(Length::Fill),
+            button("Add").on_press(Messages::Mods(Message::Add)),
+            button("Modrinth").on_press(Messages::Mods(Message::Modrinth)),
+            button("CurseForge").on_press(Messages::Mods(Message::CurseForge)),
+            button("Updates").on_press(Messages::Mods(Message::Updates)),
+            button("Open folder").on_press(Messages::Mods(Message::OpenFolder)),
+            button("Back").on_press(Messages::Mods(Message::Back)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+        let Some(mods) = &self.mods else {
+            return centering_container(column![header, text("Reading mods...")].spacing(10))
+                .into();
+        };
+
+        let visible = self.visible();
+        let all_checked = !visible.is_empty()
+            && visible
+                .iter()
+                .all(|(info, _)| self.checked.contains(&info.path));
+        let checked = self.checked.iter().cloned().collect::<Vec<_>>();
+        let bulk = |label, message: Message| {
+            button(label).on_press_maybe((!checked.is_empty()).then_some(Messages::Mods(message)))
+        };
+        let controls = row![
+            checkbox("", all_checked, |checked| {
+                Messages::Mods(Message::CheckAll(checked))
+            }),
+            text_input("Filter", &self.filter)
+                .on_input(|filter| Messages::Mods(Message::FilterChanged(filter)))
+                .width(200),
+            pick_list(&Sort::ALL[..], Some(self.sort), |sort| {
+                Messages::Mods(Message::SortSelected(sort))
+            }),
+            horizontal_space(Length::Fill),
+            bulk("Enable", Message::SetEnabled(checked.clone(), true)),
+            bulk("Disable", Message::SetEnabled(checked.clone(), false)),
+            bulk("Delete", Message::Delete),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+        let controls: Element<'_, Messages> = match &self.pending {
+            Some(pending) => row![
+                text(match pending {
+                    Pending::Delete(paths) =>
+                        format!("Delete {} mod(s)? This cannot be undone.", paths.len()),
+                    Pending::Replace(_, installed) => format!("Replace {}?", installed.join(", ")),
+                }),
+                horizontal_space(Length::Fill),
+                button(match pending {
+                    Pending::Delete(_) => "Delete",
+                    Pending::Replace(..) => "Replace",
+                })
+                .style(theme::Button::Destructive)
+                .on_press(Messages::Mods(Message::Confirm)),
+                button("Cancel").on_press(Messages::Mods(Message::Cancel)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .into(),
+            None => controls.into(),
+        };
+
+        let list = Column::with_children(
+            visible
+                .iter()
+                .map(|(info, icon)| {
+                    let is_selected = self.selected.as_ref() == Some(&info.path);
+                    let path = info.path.clone();
+                    row![
+                        checkbox("", self.checked.contains(&info.path), move |checked| {
+                            Messages::Mods(Message::Check(path.clone(), checked))
+                        }),
+                        button(
+                            row![
+                                icon_view(icon),
+                                column![
+                                    text(&info.name),
+                                    text(if info.enabled {
+                                        info.version.clone()
+                                    } else {
+                                        format!("{} (disabled)", info.version)
+                                    })
+                                    .size(12),
+                                ],
+                            ]
+                            .spacing(10)
+                            .align_items(Alignment::Center),
+                        )
+                        .style(if is_selected {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        })
+                        .width(Length::Fill)
+                        .on_press(Messages::Mods(Message::Select(info.path.clone()))),
+                        button(if info.enabled { "Disable" } else { "Enable" }).on_press(
+                            Messages::Mods(Message::SetEnabled(
+                                vec![info.path.clone()],
+                                !info.enabled
+                            ))
+                        ),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(2);
+
+        let details: Element<'_, Messages> = match self
+            .selected
+            .as_ref()
+            .and_then(|selected| mods.iter().find(|(info, _)| info.path == *selected))
+        {
+            Some((info, _)) => details(
+                info,
+                self.instance.mod_sources().get(&mods::jar_name(&info.path)),
+            ),
+            None => text(format!(
+                "{} mods installed, {} enabled",
+                mods.len(),
+                mods.iter().filter(|(info, _)| info.enabled).count()
+            ))
+            .into(),
+        };
+
+        centering_container(
+            column![
+                header,
+                controls,
+                row![
+                    scrollable(list).width(Length::FillPortion(3)),
+                    container(scrollable(details))
+                        .width(Length::FillPortion(2))
+                        .padding(10),
+                ]
+                .spacing(10)
+                .height(Length::Fill),
+            ]
+            .spacing(10),
+        )
+        .into()
+    }
+}
+
+impl From<Mods> for Screens {
+    fn from(value: Mods) -> Self {
+        Screens::Mods(value)
+    }
+}
+
+async fn select_mods() -> Vec<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Add Mods")
+        .add_filter("Mod", &["jar"])
+        .pick_files()
+        .await
+        .map(|files| {
+            files
+                .into_iter()
+                .map(|file| file.path().to_path_buf())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn icon_view(icon: &Option<Handle>) -> Element<'_, Messages> {
+    match icon {
+        Some(icon) => image(icon.clone())
+            .width(ICON_SIZE)
+            .height(ICON_SIZE)
+            .into(),
+        None => horizontal_space(ICON_SIZE).into(),
+    }
+}
+
+fn details<'a>(info: &'a ModInfo, source: Option<&ModSource>) -> Element<'a, Messages> {
+    let mut details = column![
+        text(&info.name).size(24),
+        text(format!("{} {} ({})", info.id, info.version, info.format)).size(14),
+        text(info.path.display()).size(12),
+    ]
+    .spacing(5);
+    if !info.authors.is_empty() {
+        details = details.push(text(format!("By {}", info.authors.join(", "))));
+    }
+    details = details.push(text(format!("Environment: {}", info.environment)));
+    if let Some(source) = source {
+        details = details.push(text(format!(
+            "From {}, project {}, version {}",
+            source.platform, source.project_id, source.version_id
+        )));
+    }
+    if !info.description.is_empty() {
+        details = details.push(text(&info.description));
+    }
+    if !info.provides.is_empty() {
+        details = details.push(text(format!("Provides {}", info.provides.join(", "))));
+    }
+    let dependencies = info
+        .dependencies
+        .iter()
+        .filter(|dependency| dependency.kind != DependencyKind::Optional)
+        .map(|dependency| {
+            let versions = dependency.versions.join(" or ");
+            format!("{} {} {versions}", dependency.kind, dependency.id)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    if !dependencies.is_empty() {
+        details = details.push(text(format!("Dependencies:\n{}", dependencies.join("\n"))));
+    }
+    details.into()
+}
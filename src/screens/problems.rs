@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use iced::widget::{button, column, container, row, scrollable, text, Column};
+use iced::{Alignment, Color, Command, Element, Length};
+use uuid::Uuid;
+
+use crate::dependencies::{Problem, Severity};
+use crate::instance::Instance;
+use crate::screens::main::Main;
+use crate::screens::mods::Mods;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::{dependencies, process};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Problems {
+    main: Main,
+    instance: Instance,
+    problems: Vec<Problem>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    LaunchAnyway,
+    Running(HashSet<Uuid>),
+    Mods,
+    Back,
+}
+
+impl Problems {
+    pub(crate) fn new(main: Main, instance: Instance, problems: Vec<Problem>) -> Self {
+        Problems {
+            main,
+            instance,
+            problems,
+        }
+    }
+}
+
+impl Screen for Problems {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::LaunchAnyway => (
+                Command::perform(process::running(), |running| {
+                    Messages::Problems(Message::Running(running))
+                }),
+                None,
+            ),
+            Message::Running(running) => {
+                let mut main = self.main.clone();
+                let is_running = running.contains(&self.instance.id());
+                main.set_running(running);
+                if is_running {
+                    (Command::none(), Some(main.into()))
+                } else {
+                    main.start(self.instance.clone())
+                }
+            }
+            Message::Mods => Mods::open(self.main.clone(), self.instance.clone()),
+            Message::Back => (Command::none(), Some(self.main.clone().into())),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let blocking = dependencies::is_blocking(&self.problems);
+        let title = if blocking {
+            format!("{} cannot be launched", self.instance.name())
+        } else {
+            format!("{} may not work correctly", self.instance.name())
+        };
+        let problems = Column::with_children(
+            self.problems
+                .iter()
+                .map(|problem| {
+                    text(format!("{}: {}", problem.severity, problem.message))
+                        .style(match problem.severity {
+                            Severity::Error => Color::from_rgb8(0xf0, 0x60, 0x60),
+                            Severity::Warning => Color::from_rgb8(0xf0, 0xc0, 0x50),
+                        })
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let mut buttons = row![
+            button("Mods").on_press(Messages::Problems(Message::Mods)),
+            button("Back").on_press(Messages::Problems(Message::Back)),
+        ]
+        .spacing(10);
+        if !blocking {
+            buttons = buttons
+                .push(button("Launch anyway").on_press(Messages::Problems(Message::LaunchAnyway)));
+        }
+
+        centering_container(
+            column![
+                text(title).size(24),
+                container(scrollable(problems)).height(Length::Fill),
+                buttons,
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        )
+        .into()
+    }
+}
+
+impl From<Problems> for Screens {
+    fn from(value: Problems) -> Self {
+        Screens::Problems(value)
+    }
+}
@@ -1,11 +1,12 @@
+use std::collections::HashMap;
 use std::ops::DerefMut;
 use std::path::{Path, PathBuf};
 
-use iced::widget::{button, column, horizontal_space, radio, row, text_input};
+use iced::widget::{button, column, horizontal_space, radio, row, text_input, Column};
 use iced::{Command, Element, Length};
+use serde::{Deserialize, Serialize};
 use sysinfo::{DiskExt, RefreshKind, System, SystemExt};
 
-use crate::instance::{collect_instances, Instance};
 use crate::screens::error::Error;
 use crate::screens::main::Main;
 use crate::screens::{centering_container, Messages, Screen, Screens};
@@ -13,7 +14,7 @@ use crate::{Errors, CONFIG};
 
 #[derive(Debug, Clone)]
 pub(crate) struct Setup {
-    launchers: (Option<PathBuf>, Option<PathBuf>),
+    launchers: HashMap<Launcher, PathBuf>,
     selection: Option<Launcher>,
     path: PathBuf,
 }
@@ -25,30 +26,53 @@ pub(crate) enum Message {
     Select,
     Selected(Option<PathBuf>),
     Continue,
-    InstancesLoaded(Result<Vec<Instance>, Errors>),
+    MainLoaded(Box<Result<Main, Errors>>),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub(crate) enum Launcher {
     Store,
     Legacy,
+    Package,
+    Aur,
+    Flatpak,
+    Tarball,
     Custom,
 }
 
+impl Launcher {
+    const ALL: [Launcher; 7] = [
+        Launcher::Store,
+        Launcher::Legacy,
+        Launcher::Package,
+        Launcher::Aur,
+        Launcher::Flatpak,
+        Launcher::Tarball,
+        Launcher::Custom,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Launcher::Store => "Store",
+            Launcher::Legacy => "Legacy",
+            Launcher::Package => "Package",
+            Launcher::Aur => "AUR",
+            Launcher::Flatpak => "Flatpak",
+            Launcher::Tarball => "Tarball",
+            Launcher::Custom => "Custom",
+        }
+    }
+}
+
 impl Screen for Setup {
     type Message = Message;
 
     fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
-        let (store_launcher, legacy_launcher) = &self.launchers;
         match message {
             Message::LauncherSelected(launcher) => {
                 self.selection = Some(launcher);
-                match launcher {
-                    Launcher::Store => self.path = store_launcher.clone().unwrap_or(PathBuf::new()),
-                    Launcher::Legacy => {
-                        self.path = legacy_launcher.clone().unwrap_or(PathBuf::new())
-                    }
-                    Launcher::Custom => {}
+                if launcher != Launcher::Custom {
+                    self.path = self.launchers.get(&launcher).cloned().unwrap_or_default();
                 }
                 (Command::none(), None)
             }
@@ -69,29 +93,33 @@ impl Screen for Setup {
             Message::Selected(None) => (Command::none(), None),
             Message::Continue => {
                 let path = self.path.clone();
+                let main_path = self.path.clone();
+                let launcher = self.selection.unwrap_or(Launcher::Custom);
                 (
                     Command::batch(vec![
                         Command::perform(
-                            async {
-                                CONFIG.lock().await.deref_mut().launcher_path = Some(path);
-                                CONFIG.lock().await.save().await
+                            async move {
+                                let mut config = CONFIG.lock().await;
+                                config.deref_mut().launcher_path = Some(path);
+                                config.deref_mut().launcher = Some(launcher);
+                                config.save().await
                             },
                             Messages::Save,
                         ),
-                        Command::perform(collect_instances(), |result| {
-                            Messages::Setup(Message::InstancesLoaded(result))
+                        Command::perform(Main::load(main_path), |result| {
+                            Messages::Setup(Message::MainLoaded(Box::new(result)))
                         }),
                     ]),
                     None,
                 )
             }
-            Message::InstancesLoaded(result) => (
-                Command::none(),
-                match result {
-                    Ok(instances) => Some(Main::new(self.path.clone(), instances).into()),
-                    Err(error) => Some(Error::new(error, Box::new(self.clone().into())).into()),
-                },
-            ),
+            Message::MainLoaded(result) => match *result {
+                Ok(main) => (main.check_updates(), Some(main.into())),
+                Err(error) => (
+                    Command::none(),
+                    Some(Error::new(error, Box::new(self.clone().into())).into()),
+                ),
+            },
         }
     }
 
@@ -139,32 +167,32 @@ impl Screen for Setup {
             row![path_text, path_button].spacing(10)
         };
 
+        let radios = Column::with_children(
+            Launcher::ALL
+                .into_iter()
+                .filter(|launcher| {
+                    *launcher == Launcher::Custom || self.launchers.contains_key(launcher)
+                })
+                .map(|launcher| {
+                    radio(launcher.name(), launcher, self.selection, |launcher| {
+                        Messages::Setup(Message::LauncherSelected(launcher))
+                    })
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(10);
+
         let options = column![
-            radio("Store", Launcher::Store, self.selection, |launcher| {
-                Messages::Setup(Message::LauncherSelected(launcher))
-            }),
-            radio("Legacy", Launcher::Legacy, self.selection, |launcher| {
-                Messages::Setup(Message::LauncherSelected(launcher))
-            }),
-            radio("Custom", Launcher::Custom, self.selection, |launcher| {
-                Messages::Setup(Message::LauncherSelected(launcher))
-            }),
+            radios,
             path_widget,
             row![
                 horizontal_space(Length::Fill),
-                button("Continue").on_press_maybe(
-                    if self.path.exists()
-                        && self.path.is_file()
-                        && self
-                            .path
-                            .extension()
-                            .is_some_and(|extension| extension == "exe")
-                    {
-                        Some(Messages::Setup(Message::Continue))
-                    } else {
-                        None
-                    }
-                )
+                button("Continue").on_press_maybe(if is_executable(&self.path) {
+                    Some(Messages::Setup(Message::Continue))
+                } else {
+                    None
+                })
             ]
         ];
         centering_container(options.spacing(10)).into()
@@ -181,12 +209,14 @@ pub(crate) async fn load_setup() -> Result<Setup, Errors> {
     CONFIG.lock().await.save().await?;
 
     let launchers = get_potential_locations();
-    let (selection, path) = match &launchers {
-        (None, None) => (None, PathBuf::new()),
-        (Some(launcher), None) => (Some(Launcher::Store), launcher.clone()),
-        (None, Some(launcher)) => (Some(Launcher::Legacy), launcher.clone()),
-        (Some(launcher), Some(_)) => (Some(Launcher::Store), launcher.clone()),
-    };
+    let (selection, path) = Launcher::ALL
+        .into_iter()
+        .find_map(|launcher| {
+            launchers
+                .get(&launcher)
+                .map(|path| (Some(launcher), path.clone()))
+        })
+        .unwrap_or((None, PathBuf::new()));
     Ok(Setup {
         launchers,
         selection,
@@ -195,35 +225,81 @@ pub(crate) async fn load_setup() -> Result<Setup, Errors> {
 }
 
 async fn select_launcher() -> Option<PathBuf> {
-    rfd::AsyncFileDialog::new()
-        .set_title("Select Minecraft Launcher")
-        .add_filter("Application", &["exe"])
+    let dialog = rfd::AsyncFileDialog::new().set_title("Select Minecraft Launcher");
+    #[cfg(windows)]
+    let dialog = dialog.add_filter("Application", &["exe"]);
+    dialog
         .pick_file()
         .await
         .map(|file| file.path().to_path_buf())
 }
 
-fn get_potential_locations() -> (Option<PathBuf>, Option<PathBuf>) {
-    let mut store_launcher = None;
-    let mut legacy_launcher = None;
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
 
-    for drive in get_drives() {
-        if store_launcher.is_none() {
-            let launcher = drive.join("XboxGames/Minecraft Launcher/Content/Minecraft.exe");
-            if launcher.exists() {
-                store_launcher = Some(launcher);
-            }
-        }
-        if legacy_launcher.is_none() {
-            let launcher =
-                drive.join("Program Files (x86)/Minecraft Launcher/MinecraftLauncher.exe");
-            if launcher.exists() {
-                legacy_launcher = Some(launcher);
-            }
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("exe"))
+}
+
+fn get_potential_locations() -> HashMap<Launcher, PathBuf> {
+    let mut launchers = HashMap::new();
+
+    for (launcher, path) in get_candidates() {
+        if !launchers.contains_key(&launcher) && is_executable(&path) {
+            launchers.insert(launcher, path);
         }
     }
 
-    (store_launcher, legacy_launcher)
+    launchers
+}
+
+fn get_candidates() -> Vec<(Launcher, PathBuf)> {
+    let mut candidates = vec![];
+
+    for drive in get_drives() {
+        candidates.push((
+            Launcher::Store,
+            drive.join("XboxGames/Minecraft Launcher/Content/Minecraft.exe"),
+        ));
+        candidates.push((
+            Launcher::Legacy,
+            drive.join("Program Files (x86)/Minecraft Launcher/MinecraftLauncher.exe"),
+        ));
+    }
+
+    candidates.push((
+        Launcher::Package,
+        PathBuf::from("/usr/bin/minecraft-launcher"),
+    ));
+    candidates.push((
+        Launcher::Aur,
+        PathBuf::from("/opt/minecraft-launcher/minecraft-launcher"),
+    ));
+    candidates.push((
+        Launcher::Flatpak,
+        PathBuf::from("/var/lib/flatpak/exports/bin/com.mojang.Minecraft"),
+    ));
+    if let Some(data_dir) = dirs::data_dir() {
+        candidates.push((
+            Launcher::Flatpak,
+            data_dir.join("flatpak/exports/bin/com.mojang.Minecraft"),
+        ));
+        candidates.push((
+            Launcher::Tarball,
+            data_dir.join("minecraft-launcher/minecraft-launcher"),
+        ));
+    }
+
+    candidates
 }
 
 fn get_drives() -> Vec<PathBuf> {
@@ -4,7 +4,6 @@ use std::ops::DerefMut;
 use iced::widget::text;
 use iced::{Command, Element};
 
-use crate::instance::collect_instances;
 use crate::screens::error::Error;
 use crate::screens::folder_warn::FolderNotEmptyWarn;
 use crate::screens::instance_warn::SingleInstanceWarn;
@@ -18,7 +17,7 @@ pub(crate) struct Startup;
 
 #[derive(Debug, Clone)]
 pub(crate) enum Message {
-    Loaded(Result<Screens, Errors>),
+    Loaded(Box<Result<Screens, Errors>>),
 }
 
 impl Screen for Startup {
@@ -26,16 +25,17 @@ impl Screen for Startup {
 
     fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
         let Message::Loaded(result) = message;
-        (
-            Command::none(),
-            match result {
-                Ok(screen) => Some(screen),
-                Err(error) => Some(Screens::Error(Error::new(
+        match *result {
+            Ok(Screens::Main(main)) => (main.check_updates(), Some(main.into())),
+            Ok(screen) => (Command::none(), Some(screen)),
+            Err(error) => (
+                Command::none(),
+                Some(Screens::Error(Error::new(
                     error,
                     Box::new(Screens::Startup(self.clone())),
                 ))),
-            },
-        )
+            ),
+        }
     }
 
     fn view(&self) -> Element<'_, Messages> {
@@ -70,9 +70,10 @@ pub(crate) async fn load() -> Result<Screens, Errors> {
         )
         .map_err(|error| Errors::Json(error.to_string()))?;
     }
-    if let Some(launcher_path) = &CONFIG.lock().await.launcher_path {
+    let launcher_path = CONFIG.lock().await.launcher_path.clone();
+    if let Some(launcher_path) = launcher_path {
         if launcher_path.exists() {
-            return Ok(Main::new(launcher_path.clone(), collect_instances().await?).into());
+            return Ok(Main::load(launcher_path).await?.into());
         }
     }
     load_setup().await.map(Into::into)
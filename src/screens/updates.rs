@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+
+use iced::widget::{button, checkbox, column, horizontal_space, row, scrollable, text, Column};
+use iced::{theme, Alignment, Command, Element, Length};
+
+use crate::instance::Instance;
+use crate::screens::error::{message, Error};
+use crate::screens::main::Main;
+use crate::screens::mods::Mods;
+use crate::screens::{centering_container, Messages, Screen, Screens};
+use crate::updates::{Report, Update};
+use crate::{updates, Errors};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Updates {
+    main: Main,
+    instance: Instance,
+    updates: Option<Vec<Update>>,
+    failures: Vec<(String, Errors)>,
+    checked: HashSet<String>,
+    expanded: Option<String>,
+    busy: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    Checked(Result<Report, Errors>),
+    Check(String, bool),
+    CheckAll(bool),
+    Expand(String),
+    Pin(String, bool),
+    Update,
+    Updated(Box<(Instance, Result<(), Errors>)>),
+    Refresh,
+    Back,
+}
+
+impl Updates {
+    pub(crate) fn open(main: Main, instance: Instance) -> (Command<Messages>, Option<Screens>) {
+        let updates = Updates {
+            main,
+            instance,
+            updates: None,
+            failures: vec![],
+            checked: HashSet::new(),
+            expanded: None,
+            busy: false,
+        };
+        (updates.check(), Some(updates.into()))
+    }
+
+    fn check(&self) -> Command<Messages> {
+        Command::perform(updates::check(self.instance.clone()), |result| {
+            Messages::Updates(Message::Checked(result))
+        })
+    }
+}
+
+impl Screen for Updates {
+    type Message = Message;
+
+    fn update(&mut self, message: Self::Message) -> (Command<Messages>, Option<Screens>) {
+        match message {
+            Message::Checked(Ok(Report { updates, failures })) => {
+                self.main.set_updates(self.instance.id(), updates.len());
+                self.checked = updates
+                    .iter()
+                    .map(|update| update.jar_name.clone())
+                    .collect();
+                self.updates = Some(updates);
+                self.failures = failures;
+                self.busy = false;
+                (Command::none(), None)
+            }
+            Message::Check(jar_name, checked) => {
+                if checked {
+                    self.checked.insert(jar_name);
+                } else {
+                    self.checked.remove(&jar_name);
+                }
+                (Command::none(), None)
+            }
+            Message::CheckAll(checked) => {
+                self.checked = if checked {
+                    self.updates
+                        .iter()
+                        .flatten()
+                        .map(|update| update.jar_name.clone())
+                        .collect()
+                } else {
+                    HashSet::new()
+                };
+                (Command::none(), None)
+            }
+            Message::Expand(jar_name) => {
+                self.expanded = (self.expanded.as_ref() != Some(&jar_name)).then_some(jar_name);
+                (Command::none(), None)
+            }
+            Message::Pin(jar_name, pinned) => {
+                let mut instance = self.instance.clone();
+                self.busy = true;
+                (
+                    Command::perform(
+                        async move {
+                            let result = instance.set_pinned(&jar_name, pinned).await;
+                            (instance, result)
+                        },
+                        |result| Messages::Updates(Message::Updated(Box::new(result))),
+                    ),
+                    None,
+                )
+            }
+            Message::Update => {
+                let selected = self
+                    .updates
+                    .iter()
+                    .flatten()
+                    .filter(|update| self.checked.contains(&update.jar_name))
+                    .cloned()
+                    .collect();
+                self.busy = true;
+                (
+                    Command::perform(updates::apply(self.instance.clone(), selected), |result| {
+                        Messages::Updates(Message::Updated(Box::new(result)))
+                    }),
+                    None,
+                )
+            }
+            Message::Updated(result) => {
+                let (instance, result) = *result;
+                self.main.update_instance(instance.clone());
+                self.instance = instance;
+                match result {
+                    Ok(()) => (self.check(), None),
+                    Err(error) => {
+                        self.busy = false;
+                        (
+                            Command::none(),
+                            Some(Error::new(error, Box::new(self.clone().into())).into()),
+                        )
+                    }
+                }
+            }
+            Message::Refresh => {
+                self.busy = true;
+                (self.check(), None)
+            }
+            Message::Back => Mods::open(self.main.clone(), self.instance.clone()),
+            Message::Checked(Err(error)) => {
+                self.busy = false;
+                (
+                    Command::none(),
+                    Some(Error::new(error, Box::new(self.main.clone().into())).into()),
+                )
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, Messages> {
+        let header = row![
+            text(format!("Updates for {}", self.instance.name())).size(24),
+            horizontal_space(Length::Fill),
+            button("Check again")
+                .on_press_maybe((!self.busy).then_some(Messages::Updates(Message::Refresh))),
+            button(text(format!("Update selected ({})", self.checked.len()))).on_press_maybe(
+                (!self.busy && !self.checked.is_empty())
+                    .then_some(Messages::Updates(Message::Update))
+            ),
+            button("Back").on_press(Messages::Updates(Message::Back)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+        let Some(updates) = &self.updates else {
+            return centering_container(
+                column![header, text("Checking for updates...")].spacing(10),
+            )
+            .into();
+        };
+
+        let mut list = Column::new().spacing(5);
+        for (name, error) in &self.failures {
+            list = list.push(text(format!("Could not check {name}: {}", message(error))).size(14));
+        }
+        if updates.is_empty() && self.failures.is_empty() {
+            list = list.push(text("Every linked mod is up to date."));
+        } else if !updates.is_empty() {
+            list = list.push(checkbox(
+                "Select all",
+                updates
+                    .iter()
+                    .all(|update| self.checked.contains(&update.jar_name)),
+                |checked| Messages::Updates(Message::CheckAll(checked)),
+            ));
+        }
+        for update in updates {
+            let jar_name = update.jar_name.clone();
+            let is_expanded = self.expanded.as_ref() == Some(&update.jar_name);
+            list = list.push(
+                row![
+                    checkbox(
+                        "",
+                        self.checked.contains(&update.jar_name),
+                        move |checked| {
+                            Messages::Updates(Message::Check(jar_name.clone(), checked))
+                        }
+                    ),
+                    button(column![
+                        text(&update.name),
+                        text(format!(
+                            "{} -> {} ({})",
+                            update.current, update.latest, update.platform
+                        ))
+                        .size(12),
+                    ])
+                    .style(if is_expanded {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    })
+                    .width(Length::Fill)
+                    .on_press(Messages::Updates(Message::Expand(update.jar_name.clone()))),
+                    button("Pin").on_press_maybe((!self.busy).then(|| {
+                        Messages::Updates(Message::Pin(update.jar_name.clone(), true))
+                    })),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            );
+            if is_expanded {
+                list = list.push(
+                    text(if update.changelog.is_empty() {
+                        "No changelog provided."
+                    } else {
+                        &update.changelog
+                    })
+                    .size(14),
+                );
+            }
+        }
+
+        let pinned = self
+            .instance
+            .mod_sources()
+            .iter()
+            .filter(|(_, source)| source.pinned)
+            .collect::<Vec<_>>();
+        if !pinned.is_empty() {
+            list = list.push(text("Pinned").size(20));
+            for (jar_name, _) in pinned {
+                list =
+                    list.push(
+                        row![
+                            text(jar_name).width(Length::Fill),
+                            button("Unpin").on_press_maybe((!self.busy).then(|| {
+                                Messages::Updates(Message::Pin(jar_name.clone(), false))
+                            })),
+                        ]
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                    );
+            }
+        }
+
+        centering_container(column![header, scrollable(list).height(Length::Fill)].spacing(10))
+            .into()
+    }
+}
+
+impl From<Updates> for Screens {
+    fn from(value: Updates) -> Self {
+        Screens::Updates(value)
+    }
+}
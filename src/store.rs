@@ -0,0 +1,357 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::Metadata;
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use iced::futures::lock::Mutex;
+use once_cell::sync::Lazy;
+use tokio::fs;
+
+use crate::{hash, paths, Errors};
+
+const FLUSH_DELAY: Duration = Duration::from_millis(500);
+
+static REFERENCES: Lazy<Mutex<Index>> = Lazy::new(|| Mutex::new(Index::default()));
+
+type References = BTreeMap<String, BTreeSet<PathBuf>>;
+
+#[derive(Default)]
+struct Index {
+    references: Option<References>,
+    scheduled: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Collected {
+    pub(crate) blobs: usize,
+    pub(crate) bytes: u64,
+}
+
+pub(crate) async fn link(sha1: &str, target: &Path) -> Result<bool, Errors> {
+    let sha1 = sha1.to_lowercase();
+    let blob = blob_path(&sha1)?;
+    let mut index = REFERENCES.lock().await;
+    if !blob.is_file() {
+        return Ok(false);
+    }
+    place(&blob, target).await?;
+    index.reference(&sha1, target).await?;
+    Ok(true)
+}
+
+pub(crate) async fn add(source: &Path, target: &Path) -> Result<(), Errors> {
+    let content = fs::read(source)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    let sha1 = hash::sha1(&content);
+    let blob = blob_path(&sha1)?;
+    let mut index = REFERENCES.lock().await;
+    if !blob.is_file() {
+        if let Some(parent) = blob.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|error| Errors::Io(error.kind()))?;
+        }
+        let temporary = blob.with_extension("tmp");
+        fs::write(&temporary, &content)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        fs::rename(&temporary, &blob)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+    }
+    place(&blob, target).await?;
+    index.reference(&sha1, target).await
+}
+
+pub(crate) async fn collect() -> Result<Collected, Errors> {
+    let mut index = REFERENCES.lock().await;
+    let mut references = index.load().await?.clone();
+    let mut collected = Collected::default();
+    let objects = paths::STORE.clone()?.join("objects");
+    let Ok(mut prefixes) = fs::read_dir(&objects).await else {
+        return Ok(collected);
+    };
+
+    let mut kept = References::new();
+    while let Some(prefix) = prefixes
+        .next_entry()
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?
+    {
+        let mut blobs = fs::read_dir(prefix.path())
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        while let Some(blob) = blobs
+            .next_entry()
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?
+        {
+            let sha1 = blob.file_name().to_string_lossy().to_string();
+            let metadata = blob
+                .metadata()
+                .await
+                .map_err(|error| Errors::Io(error.kind()))?;
+            let mut live = BTreeSet::new();
+            for path in references.remove(&sha1).unwrap_or_default() {
+                if is_live(&path, &sha1, metadata.len()).await {
+                    live.insert(path);
+                }
+            }
+            if live.is_empty() {
+                set_readonly(&blob.path(), false).await?;
+                fs::remove_file(blob.path())
+                    .await
+                    .map_err(|error| Errors::Io(error.kind()))?;
+                collected.blobs += 1;
+                collected.bytes += freed(&metadata);
+            } else {
+                kept.insert(sha1, live);
+            }
+        }
+        let _ = fs::remove_dir(prefix.path()).await;
+    }
+    write_references(&kept).await?;
+    index.references = Some(kept);
+    Ok(collected)
+}
+
+async fn place(blob: &Path, target: &Path) -> Result<(), Errors> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+    }
+    match unlink(target).await {
+        Err(Errors::Io(ErrorKind::NotFound)) | Ok(()) => {}
+        Err(error) => return Err(error),
+    }
+    if fs::hard_link(blob, target).await.is_err() && reflink(blob, target).is_err() {
+        fs::copy(blob, target)
+            .await
+            .map_err(|error| Errors::Io(error.kind()))?;
+        set_readonly(target, false).await?;
+    }
+    set_readonly(blob, true).await
+}
+
+pub(crate) async fn unlink(path: &Path) -> Result<(), Errors> {
+    match fs::remove_file(path).await {
+        Err(error) if error.kind() == ErrorKind::PermissionDenied => {
+            set_readonly(path, false).await?;
+            fs::remove_file(path)
+                .await
+                .map_err(|error| Errors::Io(error.kind()))
+        }
+        result => result.map_err(|error| Errors::Io(error.kind())),
+    }
+}
+
+async fn set_readonly(path: &Path, readonly: bool) -> Result<(), Errors> {
+    let mut permissions = fs::metadata(path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?
+        .permissions();
+    if permissions.readonly() == readonly {
+        return Ok(());
+    }
+    permissions.set_readonly(readonly);
+    fs::set_permissions(path, permissions)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+impl Index {
+    async fn load(&mut self) -> Result<&mut References, Errors> {
+        if self.references.is_none() {
+            self.references = Some(read_references().await?);
+        }
+        Ok(self.references.get_or_insert_with(References::new))
+    }
+
+    async fn reference(&mut self, sha1: &str, target: &Path) -> Result<(), Errors> {
+        let target = fs::canonicalize(target)
+            .await
+            .unwrap_or_else(|_| target.to_path_buf());
+        let references = self.load().await?;
+        for paths in references.values_mut() {
+            paths.remove(&target);
+        }
+        references
+            .entry(sha1.to_string())
+            .or_default()
+            .insert(target);
+        references.retain(|_, paths| !paths.is_empty());
+        if !self.scheduled {
+            self.scheduled = true;
+            tokio::spawn(flush());
+        }
+        Ok(())
+    }
+}
+
+async fn flush() {
+    tokio::time::sleep(FLUSH_DELAY).await;
+    let mut index = REFERENCES.lock().await;
+    index.scheduled = false;
+    if let Some(references) = &index.references {
+        let _ = write_references(references).await;
+    }
+}
+
+async fn is_live(path: &Path, sha1: &str, size: u64) -> bool {
+    let mut disabled = path.as_os_str().to_owned();
+    disabled.push(".disabled");
+    for candidate in [path.to_path_buf(), PathBuf::from(disabled)] {
+        if fs::metadata(&candidate)
+            .await
+            .is_ok_and(|metadata| metadata.len() == size)
+            && fs::read(&candidate)
+                .await
+                .is_ok_and(|content| hash::sha1(&content) == sha1)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+async fn read_references() -> Result<References, Errors> {
+    match fs::read_to_string(paths::STORE.clone()?.join("refs.json")).await {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(references) => Ok(references),
+            Err(_) => rebuild().await,
+        },
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(References::new()),
+        Err(error) => Err(Errors::Io(error.kind())),
+    }
+}
+
+async fn rebuild() -> Result<References, Errors> {
+    let objects = paths::STORE.clone()?.join("objects");
+    let instances = paths::INSTANCES.clone()?;
+    tokio::task::spawn_blocking(move || {
+        let blobs = files(&objects)
+            .into_iter()
+            .filter_map(|blob| {
+                let size = std::fs::metadata(&blob).ok()?.len();
+                Some((blob.file_name()?.to_string_lossy().to_string(), size))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let sizes = blobs.values().copied().collect::<HashSet<_>>();
+        let mut references = References::new();
+        for path in files(&instances) {
+            if !std::fs::metadata(&path).is_ok_and(|metadata| sizes.contains(&metadata.len())) {
+                continue;
+            }
+            let Ok(content) = std::fs::read(&path) else {
+                continue;
+            };
+            let sha1 = hash::sha1(&content);
+            if blobs.contains_key(&sha1) {
+                let path = std::fs::canonicalize(&path).unwrap_or(path);
+                references.entry(sha1).or_default().insert(path);
+            }
+        }
+        references
+    })
+    .await
+    .map_err(|_| Errors::Io(ErrorKind::Other))
+}
+
+fn files(root: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => directories.push(entry.path()),
+                Ok(file_type) if file_type.is_file() => files.push(entry.path()),
+                _ => {}
+            }
+        }
+    }
+    files
+}
+
+async fn write_references(references: &References) -> Result<(), Errors> {
+    let store = paths::STORE.clone()?;
+    fs::create_dir_all(&store)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))?;
+    let path = store.join("refs.json");
+    let temporary = path.with_extension("json.tmp");
+    fs::write(
+        &temporary,
+        serde_json::to_string(references).map_err(|error| Errors::Json(error.to_string()))?,
+    )
+    .await
+    .map_err(|error| Errors::Io(error.kind()))?;
+    fs::rename(&temporary, &path)
+        .await
+        .map_err(|error| Errors::Io(error.kind()))
+}
+
+fn blob_path(sha1: &str) -> Result<PathBuf, Errors> {
+    let prefix = sha1.get(..2).unwrap_or(sha1);
+    Ok(paths::STORE
+        .clone()?
+        .join("objects")
+        .join(prefix)
+        .join(sha1))
+}
+
+#[cfg(unix)]
+fn freed(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    if metadata.nlink() > 1 {
+        0
+    } else {
+        metadata.len()
+    }
+}
+
+#[cfg(not(unix))]
+fn freed(metadata: &Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(source: &Path, target: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let source = std::fs::File::open(source)?;
+    let file = std::fs::File::create(target)?;
+    if unsafe { libc::ioctl(file.as_raw_fd(), libc::FICLONE as _, source.as_raw_fd()) } == 0 {
+        return Ok(());
+    }
+    let error = io::Error::last_os_error();
+    drop(file);
+    let _ = std::fs::remove_file(target);
+    Err(error)
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(source: &Path, target: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let source = CString::new(source.as_os_str().as_bytes())?;
+    let target = CString::new(target.as_os_str().as_bytes())?;
+    if unsafe { libc::clonefile(source.as_ptr(), target.as_ptr(), 0) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_: &Path, _: &Path) -> io::Result<()> {
+    Err(io::Error::from(ErrorKind::Unsupported))
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use crate::api::{curseforge, modrinth};
+use crate::instance::{Instance, ModSource, Platform};
+use crate::mods::ModInfo;
+use crate::{mods, Errors};
+
+const CHANGELOGS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Update {
+    pub(crate) jar_name: String,
+    pub(crate) name: String,
+    pub(crate) platform: Platform,
+    pub(crate) current: String,
+    pub(crate) latest: String,
+    pub(crate) changelog: String,
+    target: Target,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Report {
+    pub(crate) updates: Vec<Update>,
+    pub(crate) failures: Vec<(String, Errors)>,
+}
+
+#[derive(Debug, Clone)]
+enum Target {
+    Modrinth(Box<modrinth::Version>),
+    CurseForge(Box<curseforge::Mod>, Box<curseforge::File>),
+}
+
+pub(crate) async fn check(instance: Instance) -> Result<Report, Errors> {
+    let installed = installed(&instance).await?;
+    let mut updates = vec![];
+    let mut failures = vec![];
+    for (jar_name, source) in instance.mod_sources() {
+        let Some(info) = installed.get(jar_name).filter(|_| !source.pinned) else {
+            continue;
+        };
+        let latest = match source.platform {
+            Platform::Modrinth => modrinth_update(&instance, source).await,
+            Platform::CurseForge => curseforge_update(&instance, source).await,
+        };
+        match latest {
+            Ok(Some((latest, changelog, target))) => updates.push(Update {
+                jar_name: jar_name.clone(),
+                name: info.name.clone(),
+                platform: source.platform,
+                current: info.version.clone(),
+                latest,
+                changelog,
+                target,
+            }),
+            Ok(None) => {}
+            Err(error) => failures.push((info.name.clone(), error)),
+        }
+    }
+    updates.sort_by_key(|update| update.name.to_lowercase());
+    failures.sort_by_key(|(name, _)| name.to_lowercase());
+    Ok(Report { updates, failures })
+}
+
+pub(crate) async fn count(instance: Instance) -> Result<usize, Errors> {
+    let installed = installed(&instance).await?;
+    let mut count = 0;
+    for (jar_name, source) in instance.mod_sources() {
+        if source.pinned || !installed.contains_key(jar_name) {
+            continue;
+        }
+        let available = match source.platform {
+            Platform::Modrinth => modrinth_candidates(&instance, source)
+                .await
+                .is_ok_and(|candidates| !candidates.is_empty()),
+            Platform::CurseForge => curseforge_candidates(&instance, source)
+                .await
+                .is_ok_and(|candidates| !candidates.is_empty()),
+        };
+        count += usize::from(available);
+    }
+    Ok(count)
+}
+
+async fn installed(instance: &Instance) -> Result<HashMap<String, ModInfo>, Errors> {
+    Ok(mods::scan(mods::mods_dir(instance.path()))
+        .await?
+        .into_iter()
+        .filter(|info| info.enabled)
+        .map(|info| (mods::jar_name(&info.path), info))
+        .collect())
+}
+
+pub(crate) async fn apply(
+    mut instance: Instance,
+    updates: Vec<Update>,
+) -> (Instance, Result<(), Errors>) {
+    for update in updates {
+        let result;
+        (instance, result) = match update.target {
+            Target::Modrinth(version) => modrinth::install(instance, *version).await,
+            Target::CurseForge(project, file) => {
+                curseforge::install(instance, *project, *file).await
+            }
+        };
+        if let Err(error) = result {
+            return (instance, Err(error));
+        }
+    }
+    (instance, Ok(()))
+}
+
+async fn modrinth_update(
+    instance: &Instance,
+    source: &ModSource,
+) -> Result<Option<(String, String, Target)>, Errors> {
+    let candidates = modrinth_candidates(instance, source).await?;
+    let Some(latest) = candidates.first() else {
+        return Ok(None);
+    };
+    let changelog = candidates
+        .iter()
+        .take(CHANGELOGS)
+        .map(|version| {
+            format!(
+                "{}\n{}",
+                version.version_number,
+                version.changelog.as_deref().unwrap_or_default().trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(Some((
+        latest.version_number.clone(),
+        changelog,
+        Target::Modrinth(Box::new(latest.clone())),
+    )))
+}
+
+async fn modrinth_candidates(
+    instance: &Instance,
+    source: &ModSource,
+) -> Result<Vec<modrinth::Version>, Errors> {
+    let mut versions = modrinth::versions(
+        source.project_id.clone(),
+        instance.minecraft_version().to_string(),
+        instance.loader(),
+    )
+    .await?;
+    let current = match versions
+        .iter()
+        .find(|version| version.id == source.version_id)
+    {
+        Some(current) => current.clone(),
+        None => modrinth::version(&source.version_id).await?,
+    };
+    let stability = stability(&current.version_type);
+    versions.retain(|version| {
+        version.date_published > current.date_published
+            && self::stability(&version.version_type) <= stability
+    });
+    versions.sort_by(|left, right| right.date_published.cmp(&left.date_published));
+    Ok(versions)
+}
+
+async fn curseforge_update(
+    instance: &Instance,
+    source: &ModSource,
+) -> Result<Option<(String, String, Target)>, Errors> {
+    let Ok(mod_id) = source.project_id.parse() else {
+        return Ok(None);
+    };
+    let candidates = curseforge_candidates(instance, source).await?;
+    let Some(latest) = candidates.first() else {
+        return Ok(None);
+    };
+    let mut changelogs = vec![];
+    for file in candidates.iter().take(CHANGELOGS) {
+        let changelog = curseforge::changelog(mod_id, file.id).await?;
+        changelogs.push(format!("{}\n{changelog}", file.display_name));
+    }
+    Ok(Some((
+        latest.display_name.clone(),
+        changelogs.join("\n\n"),
+        Target::CurseForge(
+            Box::new(curseforge::project(mod_id).await?),
+            Box::new(latest.clone()),
+        ),
+    )))
+}
+
+async fn curseforge_candidates(
+    instance: &Instance,
+    source: &ModSource,
+) -> Result<Vec<curseforge::File>, Errors> {
+    let (Ok(mod_id), Ok(file_id)) = (source.project_id.parse(), source.version_id.parse()) else {
+        return Ok(vec![]);
+    };
+    let mut files = curseforge::files(
+        mod_id,
+        instance.minecraft_version().to_string(),
+        instance.loader(),
+    )
+    .await?;
+    let current = match files.iter().find(|file| file.id == file_id) {
+        Some(current) => current.clone(),
+        None => curseforge::file(mod_id, file_id).await?,
+    };
+    let stability = stability(current.release_type());
+    files.retain(|file| {
+        file.file_date > current.file_date && self::stability(file.release_type()) <= stability
+    });
+    files.sort_by(|left, right| right.file_date.cmp(&left.file_date));
+    Ok(files)
+}
+
+fn stability(version_type: &str) -> u8 {
+    match version_type {
+        "release" => 0,
+        "beta" => 1,
+        _ => 2,
+    }
+}
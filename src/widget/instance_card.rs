@@ -1,6 +1,6 @@
 use iced::alignment::Horizontal;
 use iced::widget::container::Appearance;
-use iced::widget::{button, column, component, container, vertical_space, Component};
+use iced::widget::{button, column, component, container, row, text, vertical_space, Component};
 use iced::{Alignment, Background, Color, Element, Length, Renderer, Theme};
 
 use crate::instance::Instance;
@@ -11,7 +11,12 @@ where
     Message: Clone,
 {
     on_run: Message,
+    on_kill: Message,
+    on_logs: Message,
+    on_select: Message,
     is_hovered: bool,
+    is_running: bool,
+    updates: usize,
     width: Length,
     height: Length,
     instance: &'a Instance,
@@ -22,22 +27,42 @@ pub(crate) enum Event {
     Click,
     Update(bool),
     Run,
+    Kill,
+    Logs,
 }
 
 impl<'a, Message> InstanceCard<'a, Message>
 where
     Message: Clone,
 {
-    pub(crate) fn new(on_run: Message, is_hovered: bool, instance: &'a Instance) -> Self {
+    pub(crate) fn new(
+        on_run: Message,
+        on_kill: Message,
+        on_logs: Message,
+        on_select: Message,
+        is_hovered: bool,
+        is_running: bool,
+        instance: &'a Instance,
+    ) -> Self {
         Self {
             on_run,
+            on_kill,
+            on_logs,
+            on_select,
             is_hovered,
+            is_running,
+            updates: 0,
             width: Length::Shrink,
             height: Length::Shrink,
             instance,
         }
     }
 
+    pub(crate) fn updates(mut self, updates: usize) -> Self {
+        self.updates = updates;
+        self
+    }
+
     pub(crate) fn width(mut self, width: impl Into<Length>) -> Self {
         self.width = width.into();
         self
@@ -58,29 +83,63 @@ where
 
     fn update(&mut self, _: &mut Self::State, event: Self::Event) -> Option<Message> {
         match event {
-            Event::Click => None,
+            Event::Click => Some(self.on_select.clone()),
             Event::Update(is_hovered) => {
                 self.is_hovered = is_hovered;
 
                 None
             }
             Event::Run => Some(self.on_run.clone()),
+            Event::Kill => Some(self.on_kill.clone()),
+            Event::Logs => Some(self.on_logs.clone()),
         }
     }
 
     fn view(&self, _: &Self::State) -> Element<'_, Self::Event, Renderer> {
-        let bottom: Element<Self::Event> = if self.is_hovered {
-            button("Run").on_press(Event::Run).into()
+        let bottom: Element<Self::Event> = if self.is_running {
+            column![
+                text("Running"),
+                row![
+                    button("Kill").on_press(Event::Kill),
+                    button("Logs").on_press(Event::Logs),
+                ]
+                .spacing(5),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into()
+        } else if self.is_hovered {
+            row![
+                button("Run").on_press(Event::Run),
+                button("Logs").on_press(Event::Logs),
+            ]
+            .spacing(5)
+            .into()
         } else {
             self.instance.name().into()
         };
 
+        let updates: Element<Self::Event> = match self.updates {
+            0 => vertical_space(0).into(),
+            1 => text("1 update available").size(12).into(),
+            updates => text(format!("{updates} updates available")).size(12).into(),
+        };
+
         ClickHoverArea::new(
             container(
-                column!["Sample text", vertical_space(Length::Fill), bottom]
-                    .padding(10)
-                    .spacing(10)
-                    .align_items(Alignment::Center),
+                column![
+                    text(format!(
+                        "{} {}",
+                        self.instance.loader(),
+                        self.instance.minecraft_version()
+                    )),
+                    updates,
+                    vertical_space(Length::Fill),
+                    bottom
+                ]
+                .padding(10)
+                .spacing(10)
+                .align_items(Alignment::Center),
             )
             .width(self.width)
             .height(self.height)